@@ -0,0 +1,143 @@
+#![allow(clippy::needless_return)]
+
+use while_lang_data_flow_analysis::{
+    abstract_syntax::Program,
+    ae::AvailableExpressions,
+    cp::ConstantPropagation,
+    dot::{analysis_to_dot, cfg_to_dot},
+    framework::solve,
+    interval::{IntervalAnalysis, IntervalLattice},
+    lv::LiveVariables,
+    mop::solve_mop,
+    parity::{ParityAnalysis, ParityLattice},
+    parser::parse,
+    rd::ReachingDefinition,
+    report::render_result,
+    sign::{SignAnalysis, SignLattice},
+    utils::{assignments, blocks, flow, flow_r, fv_st, init, label, r#final},
+    vb::VeryBusyExpressions,
+};
+
+// Runs the textbook examples in `programs/` through every analysis
+
+fn parse_program(source: &str) -> Box<Program> {
+    return match parse(source) {
+        Ok(program) => program,
+        Err(error) => panic!("{}", error.to_diagnostic().render(source)),
+    };
+}
+
+fn main() {
+    let stmt = parse_program(include_str!("../programs/loops.while"));
+
+    let block = blocks(stmt.clone())[4].clone();
+
+    let stmt_blocks = blocks(stmt.clone());
+    let stmt_assignments = assignments(stmt.clone());
+    let stmt_fv = fv_st(stmt.clone());
+    let stmt_init = init(stmt.clone());
+    let stmt_final = r#final(stmt.clone());
+    let block_label = label(block.clone());
+    let stmt_flow = flow(stmt.clone());
+    let stmt_flow_r = flow_r(stmt.clone());
+
+    println!("stmt:\n{}", stmt);
+    println!("stmt_blocks: {:?}", stmt_blocks);
+    println!("stmt_assignments: {:?}", stmt_assignments);
+    println!("stmt_fv: {:?}", stmt_fv);
+    println!("stmt_init: {:?}", stmt_init);
+    println!("stmt_final: {:?}", stmt_final);
+    println!("block_label: {:?}", block_label);
+    println!("stmt_flow: {:?}", stmt_flow);
+    println!("stmt_flow_r: {:?}", stmt_flow_r);
+    print!("stmt_dot:\n{}", cfg_to_dot(stmt.clone(), false));
+
+    let available_expressions_program =
+        parse_program(include_str!("../programs/available_expressions.while"));
+
+    println!("Available Expressions");
+    let available_expressions_result = solve(Box::new(AvailableExpressions {
+        program: available_expressions_program,
+    }));
+    print!("{}", render_result(&available_expressions_result));
+
+    let reaching_definitions_program = parse_program(include_str!("../programs/factorial.while"));
+
+    println!("Reaching Definition");
+    let reaching_definitions_result = solve(Box::new(ReachingDefinition {
+        program: reaching_definitions_program.clone(),
+    }));
+    print!("{}", render_result(&reaching_definitions_result));
+
+    let live_variables_program = parse_program(include_str!("../programs/live_variables.while"));
+
+    println!("Live Variables");
+    let live_variables_result = solve(Box::new(LiveVariables {
+        program: live_variables_program.clone(),
+    }));
+    print!("{}", render_result(&live_variables_result));
+    print!(
+        "{}",
+        analysis_to_dot(live_variables_program, &live_variables_result)
+    );
+
+    let very_busy_expressions_program =
+        parse_program(include_str!("../programs/very_busy_expressions.while"));
+
+    println!("Very busy expressions");
+    let very_busy_expressions_result = solve(Box::new(VeryBusyExpressions {
+        program: very_busy_expressions_program,
+    }));
+    print!("{}", render_result(&very_busy_expressions_result));
+
+    let constant_propagation_program = parse_program(include_str!("../programs/constants.while"));
+
+    println!("Constant propagation");
+    let constant_propagation_result = solve(Box::new(ConstantPropagation {
+        program: constant_propagation_program,
+    }));
+    print!("{}", render_result(&constant_propagation_result));
+
+    let branches_program = parse_program(include_str!("../programs/branches.while"));
+
+    println!("Constant propagation, MFP");
+    let mfp_result = solve(Box::new(ConstantPropagation {
+        program: branches_program.clone(),
+    }));
+    print!("{}", render_result(&mfp_result));
+
+    println!("Constant propagation, MOP");
+    let mop_result = solve_mop(
+        Box::new(ConstantPropagation {
+            program: branches_program,
+        }),
+        16,
+    );
+    print!("{}", render_result(&mop_result));
+
+    println!("Interval analysis");
+    let interval_result = solve(Box::new(IntervalAnalysis::new(
+        reaching_definitions_program,
+        IntervalLattice {},
+        2,
+    )));
+    print!("{}", render_result(&interval_result));
+
+    let sign_program = parse_program(include_str!("../programs/signs.while"));
+
+    println!("Sign analysis");
+    let sign_result = solve(Box::new(SignAnalysis::new(
+        sign_program.clone(),
+        SignLattice {},
+        0,
+    )));
+    print!("{}", render_result(&sign_result));
+
+    println!("Parity analysis");
+    let parity_result = solve(Box::new(ParityAnalysis::new(
+        sign_program,
+        ParityLattice {},
+        0,
+    )));
+    print!("{}", render_result(&parity_result));
+}
@@ -1,19 +1,52 @@
+use serde::{Deserialize, Serialize};
+
 pub type Integer = i64;
 pub type Label = Integer;
 pub type Name = String;
 pub type Program = Statement;
 pub const UNDEF: Label = -1;
 
+/// Location of a block in the source text it was parsed from. Lines and
+/// columns start at 1; the default span (all zeros) marks a block that was
+/// built by hand and has no source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct Span {
+    pub start: usize, // Byte offset of the first character
+    pub end: usize,   // Byte offset one past the last character
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+    pub fn from_offsets(source: &str, start: usize, end: usize) -> Span {
+        let before = &source[..start];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let column = source[line_start..start].chars().count() + 1;
+
+        return Span {
+            start,
+            end,
+            line,
+            column,
+        };
+    }
+
+    pub fn is_unknown(&self) -> bool {
+        return self.line == 0;
+    }
+}
+
 /* Enums */
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Block {
     AssignmentStmt(AssignmentStmt),
     SkipStmt(SkipStmt),
     Condition(Condition),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Statement {
     AssignmentStmt(AssignmentStmt),
     SkipStmt(SkipStmt),
@@ -22,13 +55,13 @@ pub enum Statement {
     WhileStmt(WhileStmt),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Expression {
     ArithmeticExpression(Box<ArithmeticExpression>),
     BooleanExpression(Box<BooleanExpression>),
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ArithmeticExpression {
     VarExp(VarExp),
     NumExp(NumExp),
@@ -38,7 +71,7 @@ pub enum ArithmeticExpression {
     DivExp(DivExp),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum BooleanExpression {
     CTrue(CTrue),
     CFalse(CFalse),
@@ -53,74 +86,80 @@ pub enum BooleanExpression {
     LEqExp(LEqExp),
 }
 
-#[derive(Debug, Clone)] /* Statements */
+#[derive(Debug, Clone, Serialize, Deserialize)] /* Statements */
 pub struct AssignmentStmt {
     pub name: Name,
     pub exp: Box<Expression>,
     pub label: Label,
+    #[serde(default)]
+    pub span: Span,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SkipStmt {
     pub label: Label,
+    #[serde(default)]
+    pub span: Span,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SequenceStmt {
     pub s1: Box<Statement>,
     pub s2: Box<Statement>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IfElseStmt {
     pub condition: Condition,
     pub then_stmt: Box<Statement>,
     pub else_stmt: Box<Statement>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WhileStmt {
     pub condition: Condition,
     pub stmt: Box<Statement>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Condition {
     pub exp: Box<BooleanExpression>,
     pub label: Label,
+    #[serde(default)]
+    pub span: Span,
 }
 
 /* Arithmetic expressio
 ns */
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct VarExp {
     pub name: Name,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct NumExp {
     pub value: Integer,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct AddExp {
     pub left: Box<ArithmeticExpression>,
     pub right: Box<ArithmeticExpression>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct SubExp {
     pub left: Box<ArithmeticExpression>,
     pub right: Box<ArithmeticExpression>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct MulExp {
     pub left: Box<ArithmeticExpression>,
     pub right: Box<ArithmeticExpression>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct DivExp {
     pub left: Box<ArithmeticExpression>,
     pub right: Box<ArithmeticExpression>,
@@ -128,24 +167,24 @@ pub struct DivExp {
 
 /* Boolean expressions */
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CTrue {}
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CFalse {}
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NotExp {
     pub exp: Box<BooleanExpression>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AndExp {
     pub left: Box<BooleanExpression>,
     pub right: Box<BooleanExpression>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrExp {
     pub left: Box<BooleanExpression>,
     pub right: Box<BooleanExpression>,
@@ -153,31 +192,31 @@ pub struct OrExp {
 
 /* Relational expressions */
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EqExp {
     pub left: Box<ArithmeticExpression>,
     pub right: Box<ArithmeticExpression>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GTExp {
     pub left: Box<ArithmeticExpression>,
     pub right: Box<ArithmeticExpression>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LTExp {
     pub left: Box<ArithmeticExpression>,
     pub right: Box<ArithmeticExpression>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GEqExp {
     pub left: Box<ArithmeticExpression>,
     pub right: Box<ArithmeticExpression>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LEqExp {
     pub left: Box<ArithmeticExpression>,
     pub right: Box<ArithmeticExpression>,
@@ -0,0 +1,176 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    abstract_syntax::{Block, Label, Program},
+    framework::Edge,
+    utils::{blocks, flow, init, label, r#final},
+};
+
+/// Control flow graph of a program, computed once from `blocks`, `flow`,
+/// `init` and `final`. The reverse view swaps the direction of every edge
+/// together with the extremal and final labels, giving the graph that
+/// backward analyses are solved over.
+#[derive(Debug, Clone)]
+pub struct Cfg {
+    blocks: HashMap<Label, Box<Block>>,
+    labels: Vec<Label>,
+    edges: Vec<Edge>,
+    successors: HashMap<Label, Vec<Label>>,
+    predecessors: HashMap<Label, Vec<Label>>,
+    extremal: Vec<Label>,
+    finals: Vec<Label>,
+    reversed: bool,
+}
+
+impl Cfg {
+    pub fn new(program: Box<Program>) -> Cfg {
+        let program_blocks = blocks(program.clone());
+        let labels: Vec<Label> = program_blocks.iter().map(|b| label(b.clone())).collect();
+        let edges = flow(program.clone());
+
+        let mut successors: HashMap<Label, Vec<Label>> =
+            labels.iter().map(|l| (*l, Vec::new())).collect();
+        let mut predecessors = successors.clone();
+        for (l1, l2) in &edges {
+            successors.get_mut(l1).unwrap().push(*l2);
+            predecessors.get_mut(l2).unwrap().push(*l1);
+        }
+
+        return Cfg {
+            blocks: labels.iter().copied().zip(program_blocks).collect(),
+            labels,
+            edges,
+            successors,
+            predecessors,
+            extremal: Vec::from([init(program.clone())]),
+            finals: r#final(program),
+            reversed: false,
+        };
+    }
+
+    /// The same graph with every edge turned around, as used by `flow_r`
+    pub fn reverse(&self) -> Cfg {
+        return Cfg {
+            blocks: self.blocks.clone(),
+            labels: self.labels.clone(),
+            edges: self.edges.iter().map(|(l1, l2)| (*l2, *l1)).collect(),
+            successors: self.predecessors.clone(),
+            predecessors: self.successors.clone(),
+            extremal: self.finals.clone(),
+            finals: self.extremal.clone(),
+            reversed: !self.reversed,
+        };
+    }
+
+    pub fn is_reversed(&self) -> bool {
+        return self.reversed;
+    }
+
+    pub fn block(&self, l: Label) -> Box<Block> {
+        return self.blocks[&l].clone();
+    }
+
+    /// Labels in the order `blocks` lists them
+    pub fn labels(&self) -> &[Label] {
+        return &self.labels;
+    }
+
+    pub fn edges(&self) -> &[Edge] {
+        return &self.edges;
+    }
+
+    pub fn successors(&self, l: Label) -> &[Label] {
+        return &self.successors[&l];
+    }
+
+    pub fn predecessors(&self, l: Label) -> &[Label] {
+        return &self.predecessors[&l];
+    }
+
+    /// Labels where the graph is entered, E of the monotone framework
+    pub fn extremal(&self) -> &[Label] {
+        return &self.extremal;
+    }
+
+    /// Labels ordered so that every label comes before its successors, except
+    /// along back edges; labels unreachable from the extremal labels come last
+    pub fn reverse_postorder(&self) -> Vec<Label> {
+        let mut visited = HashSet::new();
+        let mut postorder = Vec::new();
+
+        for l in self.extremal.iter().chain(self.labels.iter()) {
+            if visited.insert(*l) {
+                self.postorder(*l, &mut visited, &mut postorder);
+            }
+        }
+
+        postorder.reverse();
+        return postorder;
+    }
+
+    fn postorder(&self, l: Label, visited: &mut HashSet<Label>, postorder: &mut Vec<Label>) {
+        for next in &self.successors[&l] {
+            if visited.insert(*next) {
+                self.postorder(*next, visited, postorder);
+            }
+        }
+        postorder.push(l);
+    }
+
+    /// Labels where the graph is left
+    pub fn finals(&self) -> &[Label] {
+        return &self.finals;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    // [x>0]^1, [y>0]^2, [y := y-1]^3, [x := x-1]^4, [z := 1]^5
+    const NESTED: &str = "while x > 0 do (while y > 0 do y := y - 1; x := x - 1); z := 1";
+
+    #[test]
+    fn reverse_turns_every_edge_around() {
+        let cfg = Cfg::new(parse(NESTED).unwrap());
+        let reversed = cfg.reverse();
+
+        assert_eq!(cfg.extremal(), [1]);
+        assert_eq!(cfg.finals(), [5]);
+        assert_eq!(reversed.extremal(), [5]);
+        assert_eq!(reversed.finals(), [1]);
+        assert!(reversed.is_reversed());
+        assert!(!reversed.reverse().is_reversed());
+
+        for (l1, l2) in cfg.edges() {
+            assert!(reversed.edges().contains(&(*l2, *l1)));
+        }
+        assert_eq!(reversed.edges().len(), cfg.edges().len());
+        for l in cfg.labels() {
+            assert_eq!(reversed.successors(*l), cfg.predecessors(*l));
+            assert_eq!(reversed.predecessors(*l), cfg.successors(*l));
+        }
+        assert_eq!(reversed.reverse().edges(), cfg.edges());
+    }
+
+    #[test]
+    fn reverse_postorder_of_a_nested_loop() {
+        let cfg = Cfg::new(parse(NESTED).unwrap());
+        assert_eq!(cfg.reverse_postorder(), [1, 5, 2, 4, 3]);
+
+        // Every edge but the back edges 3 -> 2 and 4 -> 1 points forward
+        let order = cfg.reverse_postorder();
+        let position = |l: &Label| order.iter().position(|l2| l2 == l).unwrap();
+        for (l1, l2) in cfg.edges() {
+            let back = [(3, 2), (4, 1)].contains(&(*l1, *l2));
+            assert_eq!(position(l1) < position(l2), !back);
+        }
+    }
+
+    #[test]
+    fn reverse_postorder_of_the_reversed_nested_loop() {
+        let reversed = Cfg::new(parse(NESTED).unwrap()).reverse();
+        assert_eq!(reversed.reverse_postorder(), [5, 1, 4, 2, 3]);
+    }
+}
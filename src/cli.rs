@@ -0,0 +1,529 @@
+use std::fmt;
+
+use while_lang_data_flow_analysis::worklist::Strategy;
+
+/*
+   Command line of the `dfa` binary:
+
+   dfa analyze --analysis <name>[,<name>...] [--strategy <order>] [--stats]
+                [[--trace text|latex] [--verify] | --mop <bound>]
+                [--format text|dot|json] <file>
+   dfa cfg [--reverse] [--format text|dot] <file>
+   dfa labels [--format text|json] <file>
+   dfa run [--steps <limit>] [--set <name>=<value>...] <file>
+   dfa check [--steps <limit>] [--set <name>=<value>...] <file>
+
+   `--stats` writes the work done by the solver to standard error and
+   `--trace` prints the table of worklist steps before each result, so it
+   is only available with the text format.
+   `--mop` solves over all paths of at most the given number of edges
+   instead of computing the MFP solution. The number of paths, and with it
+   the time taken, grows exponentially with the bound.
+   `--verify` checks that every result solves the data flow equations and
+   fails if one does not, so it is not available with `--mop`.
+   `--analysis` may be repeated; with `--format json` the results of all
+   analyses are written as one object keyed by their names. `-` reads the
+   program from standard input.
+   `check` runs the program and reports where the results of rd, lv and ae
+   do not hold for the run.
+   Files ending in `.json` hold a program in the format of `json`. Flags of
+   other subcommands are rejected rather than ignored.
+*/
+
+pub const USAGE: &str = "\
+usage: dfa analyze --analysis <name>[,<name>...] [--strategy <order>] [--stats]
+                   [[--trace text|latex] [--verify] | --mop <bound>]
+                   [--format text|dot|json] <file>
+       dfa cfg [--reverse] [--format text|dot] <file>
+       dfa labels [--format text|json] <file>
+       dfa run [--steps <limit>] [--set <name>=<value>...] <file>
+       dfa check [--steps <limit>] [--set <name>=<value>...] <file>
+
+analyses: rd, ae, lv, vb, cp, interval, sign, parity
+strategies: lifo (default), fifo, rpo, round-robin
+--mop enumerates every path of at most <bound> edges, which takes time
+exponential in the bound";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Analysis {
+    ReachingDefinitions,
+    AvailableExpressions,
+    LiveVariables,
+    VeryBusyExpressions,
+    ConstantPropagation,
+    Interval,
+    Sign,
+    Parity,
+}
+
+impl Analysis {
+    pub fn from_name(name: &str) -> Option<Analysis> {
+        return match name {
+            "rd" => Some(Analysis::ReachingDefinitions),
+            "ae" => Some(Analysis::AvailableExpressions),
+            "lv" => Some(Analysis::LiveVariables),
+            "vb" => Some(Analysis::VeryBusyExpressions),
+            "cp" => Some(Analysis::ConstantPropagation),
+            "interval" => Some(Analysis::Interval),
+            "sign" => Some(Analysis::Sign),
+            "parity" => Some(Analysis::Parity),
+            _ => None,
+        };
+    }
+
+    pub fn name(&self) -> &'static str {
+        return match self {
+            Analysis::ReachingDefinitions => "rd",
+            Analysis::AvailableExpressions => "ae",
+            Analysis::LiveVariables => "lv",
+            Analysis::VeryBusyExpressions => "vb",
+            Analysis::ConstantPropagation => "cp",
+            Analysis::Interval => "interval",
+            Analysis::Sign => "sign",
+            Analysis::Parity => "parity",
+        };
+    }
+}
+
+impl fmt::Display for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Analysis::ReachingDefinitions => "Reaching definitions",
+            Analysis::AvailableExpressions => "Available expressions",
+            Analysis::LiveVariables => "Live variables",
+            Analysis::VeryBusyExpressions => "Very busy expressions",
+            Analysis::ConstantPropagation => "Constant propagation",
+            Analysis::Interval => "Interval analysis",
+            Analysis::Sign => "Sign analysis",
+            Analysis::Parity => "Parity analysis",
+        };
+        return write!(f, "{}", name);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Dot,
+    Json,
+}
+
+impl Format {
+    pub fn from_name(name: &str) -> Option<Format> {
+        return match name {
+            "text" => Some(Format::Text),
+            "dot" => Some(Format::Dot),
+            "json" => Some(Format::Json),
+            _ => None,
+        };
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceFormat {
+    Text,
+    Latex,
+}
+
+impl TraceFormat {
+    pub fn from_name(name: &str) -> Option<TraceFormat> {
+        return match name {
+            "text" => Some(TraceFormat::Text),
+            "latex" => Some(TraceFormat::Latex),
+            _ => None,
+        };
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Analyze {
+        analyses: Vec<Analysis>,
+        strategy: Strategy,
+        stats: bool,
+        trace: Option<TraceFormat>,
+        mop: Option<usize>,
+        verify: bool,
+        format: Format,
+        path: String,
+    },
+    Cfg {
+        reverse: bool,
+        format: Format,
+        path: String,
+    },
+    Labels {
+        format: Format,
+        path: String,
+    },
+    Run {
+        steps: usize,
+        store: Vec<(String, i64)>,
+        path: String,
+    },
+    Check {
+        steps: usize,
+        store: Vec<(String, i64)>,
+        path: String,
+    },
+    Help,
+}
+
+// Arguments after the subcommand name, split into flags and the program path
+struct Arguments {
+    analyses: Vec<Analysis>,
+    strategy: Strategy,
+    stats: bool,
+    trace: Option<TraceFormat>,
+    mop: Option<usize>,
+    verify: bool,
+    format: Format,
+    reverse: bool,
+    steps: usize,
+    store: Vec<(String, i64)>,
+    path: Option<String>,
+}
+
+pub const DEFAULT_STEPS: usize = 10000;
+
+// The flags each subcommand takes, None for an unknown command
+fn flags_of(command: &str) -> Option<&'static [&'static str]> {
+    return match command {
+        "analyze" => Some(&[
+            "--analysis",
+            "--strategy",
+            "--stats",
+            "--trace",
+            "--mop",
+            "--verify",
+            "--format",
+        ]),
+        "cfg" => Some(&["--reverse", "--format"]),
+        "labels" => Some(&["--format"]),
+        "run" | "check" => Some(&["--steps", "--set"]),
+        _ => None,
+    };
+}
+
+fn parse_arguments(command: &str, args: &[String]) -> Result<Arguments, String> {
+    let flags = flags_of(command).ok_or_else(|| format!("unknown command '{}'", command))?;
+    let allow = |flag: &str| {
+        if flags.contains(&flag) {
+            return Ok(());
+        }
+        return Err(format!("{} does not take {}", command, flag));
+    };
+
+    let mut arguments = Arguments {
+        analyses: Vec::new(),
+        strategy: Strategy::default(),
+        stats: false,
+        trace: None,
+        mop: None,
+        verify: false,
+        format: Format::Text,
+        reverse: false,
+        steps: DEFAULT_STEPS,
+        store: Vec::new(),
+        path: None,
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--analysis" | "-a" => {
+                allow("--analysis")?;
+                let names = args
+                    .next()
+                    .ok_or_else(|| format!("missing value for {}", arg))?;
+                for name in names.split(',') {
+                    let analysis = Analysis::from_name(name)
+                        .ok_or_else(|| format!("unknown analysis '{}'", name))?;
+                    arguments.analyses.push(analysis);
+                }
+            }
+            "--strategy" | "-s" => {
+                allow("--strategy")?;
+                let name = args
+                    .next()
+                    .ok_or_else(|| format!("missing value for {}", arg))?;
+                arguments.strategy = Strategy::from_name(name)
+                    .ok_or_else(|| format!("unknown strategy '{}'", name))?;
+            }
+            "--trace" | "-t" => {
+                allow("--trace")?;
+                let name = args
+                    .next()
+                    .ok_or_else(|| format!("missing value for {}", arg))?;
+                arguments.trace = Some(
+                    TraceFormat::from_name(name)
+                        .ok_or_else(|| format!("unknown trace format '{}'", name))?,
+                );
+            }
+            "--mop" => {
+                allow("--mop")?;
+                let bound = args
+                    .next()
+                    .ok_or_else(|| format!("missing value for {}", arg))?;
+                arguments.mop = Some(
+                    bound
+                        .parse()
+                        .map_err(|_| format!("invalid path bound '{}'", bound))?,
+                );
+            }
+            "--steps" => {
+                allow("--steps")?;
+                let limit = args
+                    .next()
+                    .ok_or_else(|| format!("missing value for {}", arg))?;
+                arguments.steps = limit
+                    .parse()
+                    .map_err(|_| format!("invalid step limit '{}'", limit))?;
+            }
+            "--set" => {
+                allow("--set")?;
+                let binding = args
+                    .next()
+                    .ok_or_else(|| format!("missing value for {}", arg))?;
+                let (name, value) = binding
+                    .split_once('=')
+                    .and_then(|(name, value)| Some((name, value.parse().ok()?)))
+                    .ok_or_else(|| format!("invalid binding '{}'", binding))?;
+                arguments.store.push((name.to_string(), value));
+            }
+            "--format" | "-f" => {
+                allow("--format")?;
+                let name = args
+                    .next()
+                    .ok_or_else(|| format!("missing value for {}", arg))?;
+                arguments.format =
+                    Format::from_name(name).ok_or_else(|| format!("unknown format '{}'", name))?;
+            }
+            "--reverse" | "-r" => {
+                allow("--reverse")?;
+                arguments.reverse = true;
+            }
+            "--stats" => {
+                allow("--stats")?;
+                arguments.stats = true;
+            }
+            "--verify" => {
+                allow("--verify")?;
+                arguments.verify = true;
+            }
+            flag if flag.starts_with('-') && flag != "-" => {
+                return Err(format!("unknown option '{}'", flag));
+            }
+            path => {
+                if arguments.path.is_some() {
+                    return Err(format!("unexpected argument '{}'", path));
+                }
+                arguments.path = Some(path.to_string());
+            }
+        }
+    }
+
+    return Ok(arguments);
+}
+
+// Parses the arguments following the program name
+pub fn parse_command(args: &[String]) -> Result<Command, String> {
+    let (command, rest) = match args.split_first() {
+        Some((command, rest)) => (command.as_str(), rest),
+        None => return Err(String::from("missing command")),
+    };
+
+    if command == "help" || command == "--help" || command == "-h" {
+        return Ok(Command::Help);
+    }
+
+    let arguments = parse_arguments(command, rest)?;
+    let path = arguments
+        .path
+        .ok_or_else(|| String::from("missing program file"))?;
+
+    return match command {
+        "analyze" => {
+            if arguments.analyses.is_empty() {
+                return Err(String::from("missing --analysis"));
+            }
+            if arguments.trace.is_some() && arguments.mop.is_some() {
+                return Err(String::from("--trace cannot be used with --mop"));
+            }
+            // The trace is written in front of the result and would break a
+            // DOT or JSON document
+            if arguments.trace.is_some() && arguments.format != Format::Text {
+                return Err(String::from("--trace is only available with --format text"));
+            }
+            // A MOP solution need not solve the MFP equations
+            if arguments.verify && arguments.mop.is_some() {
+                return Err(String::from("--verify cannot be used with --mop"));
+            }
+            Ok(Command::Analyze {
+                analyses: arguments.analyses,
+                strategy: arguments.strategy,
+                stats: arguments.stats,
+                trace: arguments.trace,
+                mop: arguments.mop,
+                verify: arguments.verify,
+                format: arguments.format,
+                path,
+            })
+        }
+        "cfg" if arguments.format == Format::Json => Err(String::from("cfg has no json format")),
+        "cfg" => Ok(Command::Cfg {
+            reverse: arguments.reverse,
+            format: arguments.format,
+            path,
+        }),
+        "labels" if arguments.format == Format::Dot => {
+            Err(String::from("labels has no dot format"))
+        }
+        "labels" => Ok(Command::Labels {
+            format: arguments.format,
+            path,
+        }),
+        "run" => Ok(Command::Run {
+            steps: arguments.steps,
+            store: arguments.store,
+            path,
+        }),
+        "check" => Ok(Command::Check {
+            steps: arguments.steps,
+            store: arguments.store,
+            path,
+        }),
+        _ => Err(format!("unknown command '{}'", command)),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Result<Command, String> {
+        let args: Vec<String> = line.split_whitespace().map(String::from).collect();
+        return parse_command(&args);
+    }
+
+    #[test]
+    fn unknown_commands_are_reported_before_the_program_file() {
+        assert_eq!(parse("foo"), Err(String::from("unknown command 'foo'")));
+        assert_eq!(
+            parse("foo -a rd p.while"),
+            Err(String::from("unknown command 'foo'"))
+        );
+        assert_eq!(parse(""), Err(String::from("missing command")));
+        assert_eq!(parse("cfg"), Err(String::from("missing program file")));
+    }
+
+    #[test]
+    fn missing_values() {
+        assert_eq!(
+            parse("analyze p.while -a"),
+            Err(String::from("missing value for -a"))
+        );
+        assert_eq!(
+            parse("run p.while --steps"),
+            Err(String::from("missing value for --steps"))
+        );
+        assert_eq!(
+            parse("analyze p.while"),
+            Err(String::from("missing --analysis"))
+        );
+    }
+
+    #[test]
+    fn bad_values() {
+        assert_eq!(
+            parse("analyze -a rd,xy p.while"),
+            Err(String::from("unknown analysis 'xy'"))
+        );
+        assert_eq!(
+            parse("analyze -a rd -s random p.while"),
+            Err(String::from("unknown strategy 'random'"))
+        );
+        assert_eq!(
+            parse("run --set x p.while"),
+            Err(String::from("invalid binding 'x'"))
+        );
+        assert_eq!(
+            parse("analyze -a rd p.while q.while"),
+            Err(String::from("unexpected argument 'q.while'"))
+        );
+    }
+
+    #[test]
+    fn flags_of_other_commands_are_rejected() {
+        assert_eq!(
+            parse("cfg -a rd p.while"),
+            Err(String::from("cfg does not take --analysis"))
+        );
+        assert_eq!(
+            parse("labels --stats p.while"),
+            Err(String::from("labels does not take --stats"))
+        );
+        assert_eq!(
+            parse("run -f json p.while"),
+            Err(String::from("run does not take --format"))
+        );
+        assert_eq!(
+            parse("analyze -a rd --steps 5 p.while"),
+            Err(String::from("analyze does not take --steps"))
+        );
+        assert_eq!(
+            parse("check -r p.while"),
+            Err(String::from("check does not take --reverse"))
+        );
+    }
+
+    #[test]
+    fn rejected_combinations() {
+        assert_eq!(
+            parse("analyze -a rd -t text --mop 3 p.while"),
+            Err(String::from("--trace cannot be used with --mop"))
+        );
+        assert_eq!(
+            parse("analyze -a rd --verify --mop 3 p.while"),
+            Err(String::from("--verify cannot be used with --mop"))
+        );
+        assert_eq!(
+            parse("analyze -a rd -t text -f json p.while"),
+            Err(String::from("--trace is only available with --format text"))
+        );
+        assert_eq!(
+            parse("analyze -a rd -t latex -f dot p.while"),
+            Err(String::from("--trace is only available with --format text"))
+        );
+        assert_eq!(
+            parse("cfg -f json p.while"),
+            Err(String::from("cfg has no json format"))
+        );
+        assert_eq!(
+            parse("labels -f dot p.while"),
+            Err(String::from("labels has no dot format"))
+        );
+    }
+
+    #[test]
+    fn analyze_collects_its_flags() {
+        assert_eq!(
+            parse("analyze -a rd,lv --analysis cp -s fifo --stats -f json p.while"),
+            Ok(Command::Analyze {
+                analyses: vec![
+                    Analysis::ReachingDefinitions,
+                    Analysis::LiveVariables,
+                    Analysis::ConstantPropagation
+                ],
+                strategy: Strategy::from_name("fifo").unwrap(),
+                stats: true,
+                trace: None,
+                mop: None,
+                verify: false,
+                format: Format::Json,
+                path: String::from("p.while"),
+            })
+        );
+        assert_eq!(parse("help"), Ok(Command::Help));
+    }
+}
@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    abstract_syntax::{
+        AddExp, ArithmeticExpression, AssignmentStmt, Block, DivExp, Expression, Integer, MulExp,
+        Name, NumExp, Program, SubExp, VarExp,
+    },
+    framework::{Framework, L},
+    lattice::{Lattice, MapLattice},
+    utils::variables,
+};
+
+pub struct ConstantPropagation {
+    pub program: Box<Program>,
+}
+
+/// Flat lattice of integers: Bottom < Value(n) < Top
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Constant {
+    Bottom,
+    Value(Integer),
+    Top,
+}
+
+impl L for Constant {
+    fn key(&self) -> String {
+        return match self {
+            Constant::Bottom => String::from("⊥"),
+            Constant::Value(n) => n.to_string(),
+            Constant::Top => String::from("⊤"),
+        };
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ConstantLattice {}
+
+impl Lattice for ConstantLattice {
+    type Element = Constant;
+
+    fn bottom(&self) -> Constant {
+        Constant::Bottom
+    }
+
+    fn top(&self) -> Constant {
+        Constant::Top
+    }
+
+    fn join(&self, c1: Constant, c2: Constant) -> Constant {
+        return match (c1, c2) {
+            (Constant::Bottom, c) | (c, Constant::Bottom) => c,
+            (Constant::Value(n1), Constant::Value(n2)) if n1 == n2 => Constant::Value(n1),
+            _ => Constant::Top,
+        };
+    }
+
+    fn meet(&self, c1: Constant, c2: Constant) -> Constant {
+        return match (c1, c2) {
+            (Constant::Top, c) | (c, Constant::Top) => c,
+            (Constant::Value(n1), Constant::Value(n2)) if n1 == n2 => Constant::Value(n1),
+            _ => Constant::Bottom,
+        };
+    }
+
+    fn leq(&self, c1: Constant, c2: Constant) -> bool {
+        return match (c1, c2) {
+            (Constant::Bottom, _) | (_, Constant::Top) => true,
+            (Constant::Value(n1), Constant::Value(n2)) => n1 == n2,
+            _ => false,
+        };
+    }
+}
+
+pub type ConstantState = HashMap<Name, Constant>;
+
+/// Applies an integer operator to two abstract operands. Arithmetic wraps on
+/// overflow and division by zero has no result, so it yields Bottom.
+fn apply(
+    c1: Constant,
+    c2: Constant,
+    operator: impl Fn(Integer, Integer) -> Option<Integer>,
+) -> Constant {
+    return match (c1, c2) {
+        (Constant::Bottom, _) | (_, Constant::Bottom) => Constant::Bottom,
+        (Constant::Value(n1), Constant::Value(n2)) => match operator(n1, n2) {
+            Some(n) => Constant::Value(n),
+            None => Constant::Bottom,
+        },
+        _ => Constant::Top,
+    };
+}
+
+pub fn eval_ae(exp: Box<ArithmeticExpression>, state: &ConstantState) -> Constant {
+    return match *exp {
+        ArithmeticExpression::VarExp(VarExp { name }) => {
+            state.get(&name).copied().unwrap_or(Constant::Top)
+        }
+        ArithmeticExpression::NumExp(NumExp { value }) => Constant::Value(value),
+        ArithmeticExpression::AddExp(AddExp { left, right }) => {
+            apply(eval_ae(left, state), eval_ae(right, state), |n1, n2| {
+                Some(n1.wrapping_add(n2))
+            })
+        }
+        ArithmeticExpression::SubExp(SubExp { left, right }) => {
+            apply(eval_ae(left, state), eval_ae(right, state), |n1, n2| {
+                Some(n1.wrapping_sub(n2))
+            })
+        }
+        ArithmeticExpression::MulExp(MulExp { left, right }) => {
+            apply(eval_ae(left, state), eval_ae(right, state), |n1, n2| {
+                Some(n1.wrapping_mul(n2))
+            })
+        }
+        ArithmeticExpression::DivExp(DivExp { left, right }) => {
+            apply(eval_ae(left, state), eval_ae(right, state), |n1, n2| {
+                if n2 == 0 {
+                    None
+                } else {
+                    Some(n1.wrapping_div(n2))
+                }
+            })
+        }
+    };
+}
+
+impl Framework<MapLattice<ConstantLattice>> for ConstantPropagation {
+    fn get_program(&self) -> Box<Program> {
+        return self.program.clone();
+    }
+
+    fn get_lattice(&self) -> MapLattice<ConstantLattice> {
+        MapLattice {
+            keys: variables(self.program.clone()),
+            value: ConstantLattice {},
+        }
+    }
+
+    /// Nothing is known about the variables on entry
+    fn get_initial_e(&self) -> ConstantState {
+        self.get_lattice().top()
+    }
+
+    fn fl(&self, block: Box<Block>, entry: ConstantState) -> ConstantState {
+        return match *block {
+            Block::AssignmentStmt(AssignmentStmt {
+                name,
+                exp,
+                label: _,
+                span: _,
+            }) => {
+                let value = match *exp {
+                    Expression::ArithmeticExpression(exp) => eval_ae(exp, &entry),
+                    Expression::BooleanExpression(_) => Constant::Top,
+                };
+                let mut exit = entry;
+                exit.insert(name, value);
+                exit
+            }
+            _ => entry,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        framework::{solve, AnalysisResult},
+        parser::parse,
+    };
+
+    fn solve_cp(source: &str) -> AnalysisResult<ConstantState> {
+        return solve(Box::new(ConstantPropagation {
+            program: parse(source).unwrap(),
+        }));
+    }
+
+    #[test]
+    fn equal_constants_survive_the_join() {
+        let result = solve_cp("x := 2; y := x * 3; if y > 5 then x := 1 else x := 1; z := x + y");
+
+        assert_eq!(result.exit[&2]["y"], Constant::Value(6));
+        assert_eq!(result.entry[&6]["x"], Constant::Value(1));
+        assert_eq!(result.exit[&6]["z"], Constant::Value(7));
+    }
+
+    #[test]
+    fn different_constants_join_to_top() {
+        let result = solve_cp("if a > 0 then x := 1 else x := 2; y := x");
+        assert_eq!(result.entry[&4]["x"], Constant::Top);
+        assert_eq!(result.exit[&4]["y"], Constant::Top);
+    }
+
+    #[test]
+    fn textbook_example() {
+        // Nielson, Nielson & Hankin, constant propagation example
+        let result =
+            solve_cp("x := 2; y := 4; x := 1; if y > x then z := y else z := y * y; x := z");
+
+        assert_eq!(result.exit[&5]["z"], Constant::Value(4));
+        assert_eq!(result.exit[&6]["z"], Constant::Value(16));
+        assert_eq!(result.entry[&7]["z"], Constant::Top);
+        assert_eq!(result.exit[&7]["x"], Constant::Top);
+        assert_eq!(result.exit[&7]["y"], Constant::Value(4));
+    }
+}
@@ -0,0 +1,160 @@
+use std::fmt;
+
+use crate::{
+    abstract_syntax::{Label, Program, Span},
+    utils::{blocks, label, span},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Note,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub span: Span,
+    pub label: Option<Label>,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Note => write!(f, "note"),
+        };
+    }
+}
+
+impl Diagnostic {
+    pub fn error(message: String, span: Span) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Error,
+            message,
+            span,
+            label: None,
+        }
+    }
+
+    /// Points a diagnostic at the block labelled `l` in `program`, so analysis
+    /// findings can be reported against the source line the block came from.
+    pub fn at_label(
+        severity: Severity,
+        message: String,
+        program: Box<Program>,
+        l: Label,
+    ) -> Diagnostic {
+        let span = blocks(program)
+            .into_iter()
+            .find(|b| label(b.clone()) == l)
+            .map_or(Span::default(), span);
+
+        Diagnostic {
+            severity,
+            message,
+            span,
+            label: Some(l),
+        }
+    }
+
+    /*
+       Renders the diagnostic with an excerpt of the offending source line:
+
+       error: expected `:=`, found `+`
+         --> 2:3
+         |
+       2 | x + 1
+         |   ^
+    */
+    pub fn render(&self, source: &str) -> String {
+        let mut out = format!("{}: {}", self.severity, self.message);
+
+        if self.span.is_unknown() {
+            if let Some(l) = self.label {
+                out.push_str(&format!("\n --> label {}", l));
+            }
+            return out;
+        }
+
+        let line_text = source.lines().nth(self.span.line - 1).unwrap_or("");
+        let gutter = self.span.line.to_string().len();
+        let padding = " ".repeat(gutter);
+
+        let location = match self.label {
+            Some(l) => format!("{}:{} (label {})", self.span.line, self.span.column, l),
+            None => format!("{}:{}", self.span.line, self.span.column),
+        };
+
+        // Underline up to the end of the span or of the first line, at least one caret
+        let line_start = source
+            .get(..self.span.start)
+            .and_then(|before| before.rfind('\n'))
+            .map_or(0, |i| i + 1);
+        let underline_end = (line_start + line_text.len()).min(self.span.end);
+        let width = source
+            .get(self.span.start..underline_end.max(self.span.start))
+            .map_or(0, |text| text.chars().count())
+            .max(1);
+
+        out.push_str(&format!("\n{} --> {}", padding, location));
+        out.push_str(&format!("\n{} |", padding));
+        out.push_str(&format!("\n{} | {}", self.span.line, line_text));
+        // Tabs are copied so the caret lines up however wide they are shown
+        let indent: String = line_text
+            .chars()
+            .take(self.span.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        out.push_str(&format!("\n{} | {}{}", padding, indent, "^".repeat(width)));
+
+        return out;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    fn rendered(source: &str) -> String {
+        return parse(source).unwrap_err().to_diagnostic().render(source);
+    }
+
+    #[test]
+    fn error_in_the_middle_of_a_line() {
+        assert_eq!(
+            rendered("x := 1;\ny + 1"),
+            "error: expected `:=`, found `+`\n  --> 2:3\n  |\n2 | y + 1\n  |   ^"
+        );
+    }
+
+    #[test]
+    fn error_at_end_of_input() {
+        assert_eq!(
+            rendered("x := 1;"),
+            "error: expected a statement, found end of input\n  --> 1:8\n  |\n1 | x := 1;\n  |        ^"
+        );
+    }
+
+    #[test]
+    fn tabs_are_kept_in_front_of_the_caret() {
+        assert_eq!(
+            rendered("x := 1;\n\ty := 2 +"),
+            "error: expected an arithmetic expression, found end of input\n  --> 2:10\n  |\n2 | \ty := 2 +\n  | \t        ^"
+        );
+    }
+
+    #[test]
+    fn unknown_spans_show_the_label() {
+        let diagnostic = Diagnostic {
+            label: Some(3),
+            ..Diagnostic::error(String::from("label 3 is used by 2 blocks"), Span::default())
+        };
+        assert_eq!(
+            diagnostic.render(""),
+            "error: label 3 is used by 2 blocks\n --> label 3"
+        );
+    }
+}
@@ -0,0 +1,323 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    abstract_syntax::{
+        AddExp, AndExp, ArithmeticExpression, AssignmentStmt, Block, BooleanExpression, CFalse,
+        CTrue, Condition, DivExp, EqExp, Expression, GEqExp, GTExp, Integer, LEqExp, LTExp, Label,
+        MulExp, Name, NotExp, NumExp, OrExp, Program, SubExp, VarExp,
+    },
+    framework::{Edge, Framework},
+    lattice::{Lattice, MapLattice},
+    utils::{loop_heads, true_edges, variables},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Relation {
+    Eq,
+    NEq,
+    GT,
+    LT,
+    GEq,
+    LEq,
+}
+
+impl Relation {
+    // The relation with its operands swapped
+    pub fn flip(self) -> Relation {
+        return match self {
+            Relation::GT => Relation::LT,
+            Relation::LT => Relation::GT,
+            Relation::GEq => Relation::LEq,
+            Relation::LEq => Relation::GEq,
+            other => other,
+        };
+    }
+
+    // The relation that holds exactly when this one does not
+    pub fn negate(self) -> Relation {
+        return match self {
+            Relation::Eq => Relation::NEq,
+            Relation::NEq => Relation::Eq,
+            Relation::GT => Relation::LEq,
+            Relation::LT => Relation::GEq,
+            Relation::GEq => Relation::LT,
+            Relation::LEq => Relation::GT,
+        };
+    }
+}
+
+/*
+   Overflow policy shared by every value domain: arithmetic on Integer wraps
+   around as in the interpreter, so each abstract operator must include the
+   wrapped result whenever the exact one may leave the range of Integer.
+   Intervals go to ⊤ on overflow, signs include the signs wrapped results can
+   take, parities are unaffected as wrapping preserves them and constant
+   propagation folds with wrapping arithmetic.
+*/
+
+// A lattice of abstract integer values with the abstract counterparts of the
+// operators of While, shared by every non-relational analysis
+pub trait ValueDomain: Lattice {
+    fn constant(&self, value: Integer) -> Self::Element;
+
+    fn add(&self, v1: Self::Element, v2: Self::Element) -> Self::Element;
+    fn sub(&self, v1: Self::Element, v2: Self::Element) -> Self::Element;
+    fn mul(&self, v1: Self::Element, v2: Self::Element) -> Self::Element;
+    fn div(&self, v1: Self::Element, v2: Self::Element) -> Self::Element;
+
+    // Values x may take so that `x relation y` for some y in `other`
+    fn restrict(&self, _relation: Relation, _other: Self::Element) -> Self::Element {
+        return self.top();
+    }
+
+    // Whether `x relation y` for some x in `left` and y in `right`
+    fn satisfiable(&self, _relation: Relation, left: Self::Element, right: Self::Element) -> bool {
+        let bottom = self.bottom();
+        return left != bottom && right != bottom;
+    }
+}
+
+pub type ValueState<D> = HashMap<Name, <D as Lattice>::Element>;
+
+/// Narrowing passes the `dfa` binary runs the value analyses with, enough to
+/// recover the bounds a loop condition puts on widened intervals
+pub const NARROWING_PASSES: usize = 2;
+
+// Forward analysis mapping every variable to an abstract value of `domain`,
+// refining the state along the branches of conditions
+pub struct ValueAnalysis<D> {
+    program: Box<Program>,
+    domain: D,
+    narrowing_passes: usize,
+    // Derived from the program once, as transfers consult them on every edge
+    lattice: MapLattice<D>,
+    true_edges: HashSet<Edge>,
+}
+
+pub fn eval_ae<D: ValueDomain>(
+    domain: &D,
+    exp: Box<ArithmeticExpression>,
+    state: &ValueState<D>,
+) -> D::Element {
+    return match *exp {
+        ArithmeticExpression::VarExp(VarExp { name }) => {
+            state.get(&name).cloned().unwrap_or_else(|| domain.top())
+        }
+        ArithmeticExpression::NumExp(NumExp { value }) => domain.constant(value),
+        ArithmeticExpression::AddExp(AddExp { left, right }) => {
+            domain.add(eval_ae(domain, left, state), eval_ae(domain, right, state))
+        }
+        ArithmeticExpression::SubExp(SubExp { left, right }) => {
+            domain.sub(eval_ae(domain, left, state), eval_ae(domain, right, state))
+        }
+        ArithmeticExpression::MulExp(MulExp { left, right }) => {
+            domain.mul(eval_ae(domain, left, state), eval_ae(domain, right, state))
+        }
+        ArithmeticExpression::DivExp(DivExp { left, right }) => {
+            domain.div(eval_ae(domain, left, state), eval_ae(domain, right, state))
+        }
+    };
+}
+
+impl<D: ValueDomain + Clone> ValueAnalysis<D> {
+    pub fn new(program: Box<Program>, domain: D, narrowing_passes: usize) -> ValueAnalysis<D> {
+        return ValueAnalysis {
+            lattice: MapLattice {
+                keys: variables(program.clone()),
+                value: domain.clone(),
+            },
+            true_edges: true_edges(program.clone()).into_iter().collect(),
+            program,
+            domain,
+            narrowing_passes,
+        };
+    }
+
+    fn unreachable(&self) -> ValueState<D> {
+        return self.lattice.bottom();
+    }
+
+    // A state where some variable has no possible value describes no execution
+    fn is_unreachable(&self, state: &ValueState<D>) -> bool {
+        let bottom = self.domain.bottom();
+        return state.values().any(|v| *v == bottom);
+    }
+
+    fn normalize(&self, state: ValueState<D>) -> ValueState<D> {
+        if self.is_unreachable(&state) {
+            return self.unreachable();
+        }
+        return state;
+    }
+
+    // Restricts the state to the executions where `exp` evaluates to `holds`
+    pub fn refine(
+        &self,
+        state: ValueState<D>,
+        exp: Box<BooleanExpression>,
+        holds: bool,
+    ) -> ValueState<D> {
+        if self.is_unreachable(&state) {
+            return self.unreachable();
+        }
+
+        let lattice = &self.lattice;
+        let relation = |relation: Relation| {
+            if holds {
+                relation
+            } else {
+                relation.negate()
+            }
+        };
+
+        return match *exp {
+            BooleanExpression::CTrue(CTrue {}) => {
+                if holds {
+                    state
+                } else {
+                    self.unreachable()
+                }
+            }
+            BooleanExpression::CFalse(CFalse {}) => {
+                if holds {
+                    self.unreachable()
+                } else {
+                    state
+                }
+            }
+            BooleanExpression::NotExp(NotExp { exp }) => self.refine(state, exp, !holds),
+            BooleanExpression::AndExp(AndExp { left, right }) => {
+                if holds {
+                    let state = self.refine(state, left, true);
+                    self.refine(state, right, true)
+                } else {
+                    lattice.join(
+                        self.refine(state.clone(), left, false),
+                        self.refine(state, right, false),
+                    )
+                }
+            }
+            BooleanExpression::OrExp(OrExp { left, right }) => {
+                if holds {
+                    lattice.join(
+                        self.refine(state.clone(), left, true),
+                        self.refine(state, right, true),
+                    )
+                } else {
+                    let state = self.refine(state, left, false);
+                    self.refine(state, right, false)
+                }
+            }
+            BooleanExpression::EqExp(EqExp { left, right }) => {
+                self.refine_relation(state, left, relation(Relation::Eq), right)
+            }
+            BooleanExpression::GTExp(GTExp { left, right }) => {
+                self.refine_relation(state, left, relation(Relation::GT), right)
+            }
+            BooleanExpression::LTExp(LTExp { left, right }) => {
+                self.refine_relation(state, left, relation(Relation::LT), right)
+            }
+            BooleanExpression::GEqExp(GEqExp { left, right }) => {
+                self.refine_relation(state, left, relation(Relation::GEq), right)
+            }
+            BooleanExpression::LEqExp(LEqExp { left, right }) => {
+                self.refine_relation(state, left, relation(Relation::LEq), right)
+            }
+        };
+    }
+
+    fn refine_relation(
+        &self,
+        state: ValueState<D>,
+        left: Box<ArithmeticExpression>,
+        relation: Relation,
+        right: Box<ArithmeticExpression>,
+    ) -> ValueState<D> {
+        let left_value = eval_ae(&self.domain, left.clone(), &state);
+        let right_value = eval_ae(&self.domain, right.clone(), &state);
+
+        if !self
+            .domain
+            .satisfiable(relation, left_value.clone(), right_value.clone())
+        {
+            return self.unreachable();
+        }
+
+        let mut state = state;
+        if let ArithmeticExpression::VarExp(VarExp { name }) = *left {
+            let allowed = self.domain.restrict(relation, right_value);
+            let current = state
+                .get(&name)
+                .cloned()
+                .unwrap_or_else(|| self.domain.top());
+            state.insert(name, self.domain.meet(current, allowed));
+        }
+        if let ArithmeticExpression::VarExp(VarExp { name }) = *right {
+            let allowed = self.domain.restrict(relation.flip(), left_value);
+            let current = state
+                .get(&name)
+                .cloned()
+                .unwrap_or_else(|| self.domain.top());
+            state.insert(name, self.domain.meet(current, allowed));
+        }
+
+        return self.normalize(state);
+    }
+}
+
+impl<D: ValueDomain + Clone> Framework<MapLattice<D>> for ValueAnalysis<D> {
+    fn get_program(&self) -> Box<Program> {
+        return self.program.clone();
+    }
+
+    fn get_lattice(&self) -> MapLattice<D> {
+        return self.lattice.clone();
+    }
+
+    // Nothing is known about the variables on entry
+    fn get_initial_e(&self) -> ValueState<D> {
+        self.lattice.top()
+    }
+
+    fn fl(&self, block: Box<Block>, entry: ValueState<D>) -> ValueState<D> {
+        return match *block {
+            Block::AssignmentStmt(AssignmentStmt {
+                name,
+                exp,
+                label: _,
+                span: _,
+            }) => {
+                let value = match *exp {
+                    Expression::ArithmeticExpression(exp) => eval_ae(&self.domain, exp, &entry),
+                    Expression::BooleanExpression(_) => self.domain.top(),
+                };
+                let mut exit = entry;
+                exit.insert(name, value);
+                self.normalize(exit)
+            }
+            _ => entry,
+        };
+    }
+
+    fn fl_edge(&self, edge: Edge, block: Box<Block>, entry: ValueState<D>) -> ValueState<D> {
+        return match *block {
+            Block::Condition(Condition {
+                exp,
+                label: _,
+                span: _,
+            }) => {
+                let holds = self.true_edges.contains(&edge);
+                self.refine(entry, exp, holds)
+            }
+            _ => self.fl(block, entry),
+        };
+    }
+
+    fn get_widening_points(&self) -> Vec<Label> {
+        loop_heads(self.program.clone())
+    }
+
+    fn get_narrowing_passes(&self) -> usize {
+        return self.narrowing_passes;
+    }
+}
@@ -0,0 +1,170 @@
+use crate::{
+    abstract_syntax::{Block, Label, Program},
+    framework::{AnalysisResult, Edge, L},
+    utils::{blocks, flow, flow_r, init, label, r#final},
+};
+
+/*
+   Graphviz export of the control flow graph, e.g.
+
+   digraph cfg {
+       node [shape=box, fontname="monospace"];
+       entry [shape=point];
+       exit [shape=doublecircle, label="", width=0.15];
+       1 [label="[x := a+b]^1"];
+       ...
+       entry -> 1;
+       1 -> 2;
+       ...
+   }
+
+   Conditions are drawn as diamonds. The `entry` and `exit` nodes point at
+   init(S) and away from final(S), in the direction the edges are drawn.
+*/
+
+pub fn escape(text: &str) -> String {
+    return text
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\l");
+}
+
+fn node_shape(block: &Block) -> &'static str {
+    return match block {
+        Block::Condition(_) => "diamond",
+        _ => "box",
+    };
+}
+
+/// Writes the graph with `node_label` providing the text of every block
+pub fn render_graph(
+    program: Box<Program>,
+    edges: Vec<Edge>,
+    reverse: bool,
+    node_label: impl Fn(Label, Box<Block>) -> String,
+) -> String {
+    let mut out = String::from("digraph cfg {\n");
+    out.push_str("    node [shape=box, fontname=\"monospace\"];\n");
+    out.push_str("    entry [shape=point];\n");
+    out.push_str("    exit [shape=doublecircle, label=\"\", width=0.15];\n");
+
+    for block in blocks(program.clone()) {
+        let l = label(block.clone());
+        out.push_str(&format!(
+            "    {} [shape={}, label=\"{}\"];\n",
+            l,
+            node_shape(&block),
+            escape(&node_label(l, block))
+        ));
+    }
+
+    let start = init(program.clone());
+    let ends = r#final(program);
+
+    if !reverse {
+        out.push_str(&format!("    entry -> {};\n", start));
+    } else {
+        for l in &ends {
+            out.push_str(&format!("    entry -> {};\n", l));
+        }
+    }
+
+    for (l1, l2) in edges {
+        out.push_str(&format!("    {} -> {};\n", l1, l2));
+    }
+
+    if !reverse {
+        for l in &ends {
+            out.push_str(&format!("    {} -> exit;\n", l));
+        }
+    } else {
+        out.push_str(&format!("    {} -> exit;\n", start));
+    }
+
+    out.push_str("}\n");
+    return out;
+}
+
+/// Control flow graph of the program, with the edges of `flow_r` when `reverse`
+pub fn cfg_to_dot(program: Box<Program>, reverse: bool) -> String {
+    let edges = if reverse {
+        flow_r(program.clone())
+    } else {
+        flow(program.clone())
+    };
+
+    return render_graph(program, edges, reverse, |_, block| block.to_string());
+}
+
+/// Control flow graph with every block annotated by its ENTRY and EXIT values
+/// from `result`. The result is oriented along the program, so the edges of
+/// `flow` are drawn even for backward analyses.
+pub fn analysis_to_dot<E: L>(program: Box<Program>, result: &AnalysisResult<E>) -> String {
+    return render_graph(program.clone(), flow(program), false, |l, block| {
+        format!(
+            "ENTRY: {}\n{}\nEXIT: {}\n",
+            result.entry[&l].key(),
+            block,
+            result.exit[&l].key()
+        )
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{framework::solve, lv::LiveVariables, parser::parse};
+
+    const SOURCE: &str = "if x > 0 then y := 1 else skip; while y < 3 do y := y + 1";
+
+    #[test]
+    fn cfg_with_an_if_and_a_while() {
+        assert_eq!(
+            cfg_to_dot(parse(SOURCE).unwrap(), false),
+            r#"digraph cfg {
+    node [shape=box, fontname="monospace"];
+    entry [shape=point];
+    exit [shape=doublecircle, label="", width=0.15];
+    1 [shape=diamond, label="[x>0]^1"];
+    2 [shape=box, label="[y := 1]^2"];
+    3 [shape=box, label="[skip]^3"];
+    4 [shape=diamond, label="[y<3]^4"];
+    5 [shape=box, label="[y := y+1]^5"];
+    entry -> 1;
+    1 -> 2;
+    1 -> 3;
+    4 -> 5;
+    5 -> 4;
+    2 -> 4;
+    3 -> 4;
+    4 -> exit;
+}
+"#
+        );
+    }
+
+    #[test]
+    fn reversed_cfg_enters_at_the_final_labels() {
+        let dot = cfg_to_dot(parse(SOURCE).unwrap(), true);
+        assert!(dot.contains("    entry -> 4;\n"));
+        assert!(dot.contains("    4 -> 2;\n"));
+        assert!(dot.contains("    1 -> exit;\n"));
+        assert!(!dot.contains("entry -> 1;"));
+    }
+
+    #[test]
+    fn analysis_nodes_show_entry_and_exit() {
+        let program = parse(SOURCE).unwrap();
+        let result = solve(Box::new(LiveVariables {
+            program: program.clone(),
+        }));
+        let dot = analysis_to_dot(program, &result);
+        assert!(dot.contains(r#"    4 [shape=diamond, label="ENTRY: {y}\l[y<3]^4\lEXIT: {y}\l"];"#));
+        assert!(dot.contains("    entry -> 1;\n"));
+    }
+
+    #[test]
+    fn labels_are_escaped() {
+        assert_eq!(escape("say \"hi\"\\\n"), "say \\\"hi\\\"\\\\\\l");
+    }
+}
@@ -1,45 +1,174 @@
 use std::{
-    collections::{HashMap, HashSet, LinkedList},
-    fmt::Debug,
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
     hash::Hash,
+    iter::Peekable,
+    str::Chars,
+    time::Instant,
 };
 
+use serde::{Deserialize, Serialize};
+
 use crate::{
-    abstract_syntax::{Block, Label, Program},
-    utils::{blocks, label, labels},
+    abstract_syntax::{ArithmeticExpression, Block, Label, Name, Program},
+    cfg::Cfg,
+    lattice::Lattice,
+    stats::SolverStats,
+    trace::{Trace, TraceStep},
+    worklist::{new_worklist, Strategy},
 };
 
 pub type Edge = (Label, Label);
 
+/// ENTRY and EXIT sets of every label, oriented along the program's control
+/// flow regardless of the direction the analysis was solved in, together with
+/// the work it took to compute them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisResult<E> {
+    pub entry: HashMap<Label, E>,
+    pub exit: HashMap<Label, E>,
+    pub stats: SolverStats,
+}
+
+/// Textbook notation of a property, e.g. `(x, 1)` or `a+b`. Reports print
+/// properties by their key and sort them with `compare_keys`, so the output
+/// only changes when the result does.
 pub trait L {
     fn key(&self) -> String;
 }
 
-// L is the property space
-pub trait Framework<L: Eq + Hash + Clone + Debug> {
+/// Variables, the properties of live variables
+impl L for Name {
+    fn key(&self) -> String {
+        return self.clone();
+    }
+}
+
+/// Expressions, the properties of available and very busy expressions
+impl L for ArithmeticExpression {
+    fn key(&self) -> String {
+        return self.to_string();
+    }
+}
+
+/// Sets in braces, ordered by the keys of their elements
+impl<T: L> L for HashSet<T> {
+    fn key(&self) -> String {
+        let mut keys: Vec<String> = self.iter().map(|e| e.key()).collect();
+        keys.sort_by(|k1, k2| compare_keys(k1, k2));
+        return format!("{{{}}}", keys.join(", "));
+    }
+}
+
+/// Abstract stores as `{x: v, ...}`, ordered by variable
+impl<V: L> L for HashMap<Name, V> {
+    fn key(&self) -> String {
+        let mut names: Vec<&Name> = self.keys().collect();
+        names.sort_by(|x1, x2| compare_keys(x1, x2));
+
+        let bindings: Vec<String> = names
+            .into_iter()
+            .map(|x| format!("{}: {}", x, self[x].key()))
+            .collect();
+        return format!("{{{}}}", bindings.join(", "));
+    }
+}
+
+/// Orders keys as text, except that runs of digits compare by their value,
+/// so (x, 2) comes before (x, 10) and leading zeros make no difference
+pub fn compare_keys(k1: &str, k2: &str) -> Ordering {
+    let mut chars1 = k1.chars().peekable();
+    let mut chars2 = k2.chars().peekable();
+
+    loop {
+        let (c1, c2) = match (chars1.peek(), chars2.peek()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(c1), Some(c2)) => (*c1, *c2),
+        };
+
+        if c1.is_ascii_digit() && c2.is_ascii_digit() {
+            let digits = |chars: &mut Peekable<Chars>| {
+                let mut run = String::new();
+                while let Some(c) = chars.next_if(|c| c.is_ascii_digit()) {
+                    run.push(c);
+                }
+                return run.trim_start_matches('0').to_string();
+            };
+            let n1 = digits(&mut chars1);
+            let n2 = digits(&mut chars2);
+            let ordering = n1.len().cmp(&n2.len()).then(n1.cmp(&n2));
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+            continue;
+        }
+
+        if c1 != c2 {
+            return c1.cmp(&c2);
+        }
+        chars1.next();
+        chars2.next();
+    }
+}
+
+/// L is the lattice of properties
+pub trait Framework<L: Lattice> {
     fn get_program(&self) -> Box<Program>;
 
     fn is_backwards(&self) -> bool {
         return false;
     }
 
-    fn get_f(&self) -> Vec<Edge>; // Flow graph
-    fn get_e(&self) -> Vec<Label>; // Start labels
-    fn get_initial_e(&self) -> HashSet<L>; // Initial values for l in E,
-    fn get_initial_others(&self) -> HashSet<L>; // Initial values for l not in E but in F
+    /// Flow graph F with the start labels E as its extremal labels, reversed
+    /// for backward analyses
+    fn get_cfg(&self) -> Cfg {
+        let cfg = Cfg::new(self.get_program());
+        if self.is_backwards() {
+            return cfg.reverse();
+        }
+        return cfg;
+    }
+
+    fn get_lattice(&self) -> L; // Property space
+    fn get_initial_e(&self) -> L::Element; // Initial values for l in E,
 
-    // Set comparator function
-    fn set_compare(&self, set1: HashSet<L>, set2: HashSet<L>) -> bool;
+    /// Initial values for l not in E but in F
+    fn get_initial_others(&self) -> L::Element {
+        return self.get_lattice().bottom();
+    }
 
-    // Set union function
-    fn set_union(&self, set1: HashSet<L>, set2: HashSet<L>) -> HashSet<L>;
+    /// Transfer function
+    fn fl(&self, block: Box<Block>, entry: L::Element) -> L::Element;
+
+    /// Transfer function along a specific edge of F, lets analyses refine the
+    /// value leaving a condition by the branch taken
+    fn fl_edge(&self, _edge: Edge, block: Box<Block>, entry: L::Element) -> L::Element {
+        return self.fl(block, entry);
+    }
 
-    fn kill(&self, block: Box<Block>) -> HashSet<L>;
-    fn gen(&self, block: Box<Block>) -> HashSet<L>;
+    /// Labels where the solver widens instead of joining, needed for lattices
+    /// of infinite height to reach a fixpoint
+    fn get_widening_points(&self) -> Vec<Label> {
+        return Vec::new();
+    }
+
+    /// Number of descending passes run after the fixpoint to recover the
+    /// precision lost by widening
+    fn get_narrowing_passes(&self) -> usize {
+        return 0;
+    }
+}
 
-    // Transfer function
-    fn fl(&self, block: Box<Block>, entry: HashSet<L>) -> HashSet<L> {
-        let mut exit: HashSet<L> = entry.into_iter().collect();
+/// Analyses over powerset lattices whose transfer functions remove the killed
+/// properties and add the generated ones
+pub trait GenKill<T: Eq + Hash + Clone> {
+    fn kill(&self, block: Box<Block>) -> HashSet<T>;
+    fn gen(&self, block: Box<Block>) -> HashSet<T>;
+
+    fn fl_gen_kill(&self, block: Box<Block>, entry: HashSet<T>) -> HashSet<T> {
+        let mut exit: HashSet<T> = entry.into_iter().collect();
 
         let killed = self.kill(block.clone());
         for e in killed {
@@ -55,66 +184,202 @@ pub trait Framework<L: Eq + Hash + Clone + Debug> {
     }
 }
 
-pub fn solve<L: Eq + Hash + Clone + Debug>(framework: Box<dyn Framework<L>>) {
+pub fn solve<L: Lattice>(framework: Box<dyn Framework<L>>) -> AnalysisResult<L::Element> {
+    return solve_with(framework, Strategy::default());
+}
+
+/// Maximal fixed point solution, revisiting edges in the order of `strategy`
+pub fn solve_with<L: Lattice>(
+    framework: Box<dyn Framework<L>>,
+    strategy: Strategy,
+) -> AnalysisResult<L::Element> {
+    return mfp(framework, strategy, None);
+}
+
+/// Like `solve_with`, also recording every step of the worklist iteration
+pub fn solve_traced<L: Lattice>(
+    framework: Box<dyn Framework<L>>,
+    strategy: Strategy,
+) -> (AnalysisResult<L::Element>, Trace<L::Element>) {
+    let mut trace = Trace {
+        labels: Vec::new(),
+        steps: Vec::new(),
+    };
+    let result = mfp(framework, strategy, Some(&mut trace));
+    return (result, trace);
+}
+
+fn mfp<L: Lattice>(
+    framework: Box<dyn Framework<L>>,
+    strategy: Strategy,
+    mut trace: Option<&mut Trace<L::Element>>,
+) -> AnalysisResult<L::Element> {
     // Initialization
-    let mut w = LinkedList::new();
-    let mut analysis: HashMap<Label, HashSet<L>> = HashMap::new();
+    let start = Instant::now();
+    let mut stats = SolverStats::default();
+    let mut analysis: HashMap<Label, L::Element> = HashMap::new();
 
-    let program = framework.get_program();
-    let f = framework.get_f();
-    let e = framework.get_e();
+    let lattice = framework.get_lattice();
+    let cfg = framework.get_cfg();
+    let mut w = new_worklist(strategy, &cfg);
+    let e = cfg.extremal();
     let initial_e = framework.get_initial_e();
     let initial_others = framework.get_initial_others();
-    let blocks_map: HashMap<Label, Box<Block>> = blocks(program.clone())
-        .into_iter()
-        .map(|b| (label(b.clone()), b.clone()))
-        .collect();
-    let program_labels = labels(program.clone());
+    let widening_points = framework.get_widening_points();
 
-    for (l1, l2) in &f.clone() {
-        w.push_front((*l1, *l2));
+    for edge in cfg.edges() {
+        w.insert(*edge);
     }
 
-    for l in program_labels {
-        if e.contains(&l) {
-            analysis.insert(l, initial_e.clone());
+    for l in cfg.labels() {
+        if e.contains(l) {
+            analysis.insert(*l, initial_e.clone());
         } else {
-            analysis.insert(l, initial_others.clone());
+            analysis.insert(*l, initial_others.clone());
         }
     }
 
+    let snapshot = |analysis: &HashMap<Label, L::Element>| -> Vec<L::Element> {
+        return cfg.labels().iter().map(|l| analysis[l].clone()).collect();
+    };
+    if let Some(trace) = trace.as_deref_mut() {
+        trace.labels = cfg.labels().to_vec();
+        trace.steps.push(TraceStep {
+            worklist: w.contents(),
+            edge: None,
+            updated: None,
+            analysis: snapshot(&analysis),
+        });
+    }
+
     // Iteration
-    while let Some((l1, l2)) = w.pop_front() {
-        println!("W: {:?}", w);
-        let exit = framework.fl(blocks_map[&l1].clone(), analysis[&l1].clone());
+    loop {
+        let worklist = match trace {
+            Some(_) => w.contents(),
+            None => Vec::new(),
+        };
+        let Some((l1, l2)) = w.extract() else {
+            break;
+        };
+        let mut updated = None;
+
+        stats.record_pop(l1);
+        stats.record_transfer(l1);
+        let exit = framework.fl_edge((l1, l2), cfg.block(l1), analysis[&l1].clone());
         let entry = analysis[&l2].clone();
 
-        if !framework.set_compare(exit.clone(), entry.clone()) {
-            analysis.insert(l2, framework.set_union(entry.clone(), exit.clone()));
+        if !lattice.leq(exit.clone(), entry.clone()) {
+            if widening_points.contains(&l2) {
+                analysis.insert(l2, lattice.widen(entry.clone(), exit.clone()));
+            } else {
+                analysis.insert(l2, lattice.join(entry.clone(), exit.clone()));
+            }
+            stats.record_update(l2);
+            updated = Some(l2);
 
-            for (_l2, l3) in &f.clone() {
-                if *_l2 == l2 {
-                    w.push_front((l2, *l3));
-                }
+            for l3 in cfg.successors(l2) {
+                w.insert((l2, *l3));
             }
         }
+
+        if let Some(trace) = trace.as_deref_mut() {
+            trace.steps.push(TraceStep {
+                worklist,
+                edge: Some((l1, l2)),
+                updated,
+                analysis: snapshot(&analysis),
+            });
+        }
     }
 
-    // Present result
-    for (label, result) in analysis {
-        println!("label {}", label);
-        if !framework.is_backwards() {
-            println!("  ENTRY: {:?}", result);
-            println!(
-                "  EXIT: {:?}",
-                framework.fl(blocks_map[&label].clone(), result.clone())
-            );
+    // Narrowing: recompute every label from its predecessors, narrowing at
+    // the widening points, until stable or out of passes
+    for _ in 0..framework.get_narrowing_passes() {
+        let mut changed = false;
+
+        for l in cfg.labels() {
+            let l = *l;
+            let mut value = if e.contains(&l) {
+                initial_e.clone()
+            } else {
+                initial_others.clone()
+            };
+            for l1 in cfg.predecessors(l) {
+                stats.record_transfer(*l1);
+                let exit = framework.fl_edge((*l1, l), cfg.block(*l1), analysis[l1].clone());
+                value = lattice.join(value, exit);
+            }
+
+            if widening_points.contains(&l) {
+                value = lattice.narrow(analysis[&l].clone(), value);
+            }
+
+            if value != analysis[&l] {
+                analysis.insert(l, value);
+                stats.record_update(l);
+                changed = true;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    // Orient result: the worklist computes ENTRY for forward analyses and
+    // EXIT for backward ones, the other side is obtained by the transfer function
+    stats.time = start.elapsed();
+    let mut result = AnalysisResult {
+        entry: HashMap::new(),
+        exit: HashMap::new(),
+        stats,
+    };
+
+    for (label, value) in analysis {
+        let transferred = framework.fl(cfg.block(label), value.clone());
+        if !cfg.is_reversed() {
+            result.entry.insert(label, value);
+            result.exit.insert(label, transferred);
         } else {
-            println!(
-                "  ENTRY: {:?}",
-                framework.fl(blocks_map[&label].clone(), result.clone())
-            );
-            println!("  EXIT: {:?}", result);
+            result.entry.insert(label, transferred);
+            result.exit.insert(label, value);
         }
     }
+
+    return result;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_compare_by_value() {
+        assert_eq!(compare_keys("(x, 2)", "(x, 10)"), Ordering::Less);
+        assert_eq!(compare_keys("(x, 10)", "(x, 2)"), Ordering::Greater);
+        assert_eq!(compare_keys("(x, 10)", "(x, 10)"), Ordering::Equal);
+        assert_eq!(compare_keys("(x, 99)", "(y, 1)"), Ordering::Less);
+    }
+
+    #[test]
+    fn leading_zeros_are_ignored() {
+        assert_eq!(compare_keys("x007", "x7"), Ordering::Equal);
+        assert_eq!(compare_keys("x009", "x10"), Ordering::Less);
+        assert_eq!(compare_keys("x0", "x000"), Ordering::Equal);
+        assert_eq!(compare_keys("x010", "x9"), Ordering::Greater);
+    }
+
+    #[test]
+    fn text_around_numbers_compares_by_character() {
+        assert_eq!(compare_keys("x1y", "x1z"), Ordering::Less);
+        assert_eq!(compare_keys("x", "x1"), Ordering::Less);
+        assert_eq!(compare_keys("a10", "b2"), Ordering::Less);
+        // Digits sort before letters
+        assert_eq!(compare_keys("a10", "ab"), Ordering::Less);
+        assert_eq!(compare_keys("x2y10", "x2y9"), Ordering::Greater);
+        assert_eq!(
+            compare_keys("{(x, 2), (y, ?)}", "{(x, 10)}"),
+            Ordering::Less
+        );
+    }
 }
@@ -0,0 +1,299 @@
+use crate::{
+    abstract_syntax::{
+        AddExp, AndExp, ArithmeticExpression, AssignmentStmt, BooleanExpression, CFalse, CTrue,
+        Condition, DivExp, EqExp, Expression, GEqExp, GTExp, IfElseStmt, Integer, LEqExp, LTExp,
+        MulExp, Name, NotExp, NumExp, OrExp, Program, SequenceStmt, SkipStmt, Span, Statement,
+        SubExp, VarExp, WhileStmt, UNDEF,
+    },
+    labelling::relabel,
+};
+
+/*
+   Random While programs for property based testing. The same seed and
+   configuration always produce the same program, and every program is
+   labelled 1, 2, ... by `labelling::relabel`.
+
+   `depth` bounds the nesting of compound statements, a statement at depth 0
+   is a single assignment or skip. Expressions draw their operators from the
+   configured lists only, so a test can restrict the mix, e.g. leave out
+   division to avoid faults when running the program.
+*/
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArithmeticOperator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BooleanOperator {
+    True,
+    False,
+    Not,
+    And,
+    Or,
+    Eq,
+    GT,
+    LT,
+    GEq,
+    LEq,
+}
+
+impl BooleanOperator {
+    // True for the operators whose operands are boolean expressions
+    fn is_connective(&self) -> bool {
+        return matches!(
+            self,
+            BooleanOperator::Not | BooleanOperator::And | BooleanOperator::Or
+        );
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub depth: usize,            // Nesting of compound statements
+    pub expression_depth: usize, // Nesting of operators in an expression
+    pub variables: usize,        // Number of distinct variable names
+    pub min_constant: Integer,   // Constants are drawn from min_constant..=max_constant,
+    pub max_constant: Integer,   // and now and then are Integer::MIN or Integer::MAX
+    pub arithmetic: Vec<ArithmeticOperator>,
+    pub boolean: Vec<BooleanOperator>,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        return Config {
+            depth: 3,
+            expression_depth: 2,
+            variables: 3,
+            min_constant: -10,
+            max_constant: 10,
+            arithmetic: Vec::from([
+                ArithmeticOperator::Add,
+                ArithmeticOperator::Sub,
+                ArithmeticOperator::Mul,
+                ArithmeticOperator::Div,
+            ]),
+            boolean: Vec::from([
+                BooleanOperator::True,
+                BooleanOperator::False,
+                BooleanOperator::Not,
+                BooleanOperator::And,
+                BooleanOperator::Or,
+                BooleanOperator::Eq,
+                BooleanOperator::GT,
+                BooleanOperator::LT,
+                BooleanOperator::GEq,
+                BooleanOperator::LEq,
+            ]),
+        };
+    }
+}
+
+/// SplitMix64, small and good enough to spread programs over the space
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        return Rng { state: seed };
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        return z ^ (z >> 31);
+    }
+
+    /// Uniform in 0..n, n must not be 0
+    pub fn below(&mut self, n: usize) -> usize {
+        return (self.next_u64() % n as u64) as usize;
+    }
+
+    /// True with the given probability in percent
+    pub fn chance(&mut self, percent: usize) -> bool {
+        return self.below(100) < percent;
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        return &items[self.below(items.len())];
+    }
+}
+
+pub struct Generator {
+    pub config: Config,
+    rng: Rng,
+}
+
+impl Generator {
+    pub fn new(config: Config, seed: u64) -> Generator {
+        return Generator {
+            config,
+            rng: Rng::new(seed),
+        };
+    }
+
+    /// Next program, labelled 1, 2, ... in the order of `utils::blocks`
+    pub fn program(&mut self) -> Box<Program> {
+        let stmt = self.statement(self.config.depth);
+        return relabel(stmt);
+    }
+
+    fn name(&mut self) -> Name {
+        let i = self.rng.below(self.config.variables.max(1));
+        if i < 26 {
+            return ((b'a' + i as u8) as char).to_string();
+        }
+        return format!("v{}", i);
+    }
+
+    pub fn statement(&mut self, depth: usize) -> Box<Statement> {
+        // Leaves get more likely towards the bottom so trees vary in shape
+        if depth == 0 || self.rng.chance(20) {
+            if self.rng.chance(85) {
+                return Box::new(Statement::AssignmentStmt(AssignmentStmt {
+                    name: self.name(),
+                    exp: self.expression(),
+                    label: UNDEF,
+                    span: Span::default(),
+                }));
+            }
+            return Box::new(Statement::SkipStmt(SkipStmt {
+                label: UNDEF,
+                span: Span::default(),
+            }));
+        }
+
+        return Box::new(match self.rng.below(4) {
+            0 | 1 => Statement::SequenceStmt(SequenceStmt {
+                s1: self.statement(depth - 1),
+                s2: self.statement(depth - 1),
+            }),
+            2 => Statement::IfElseStmt(IfElseStmt {
+                condition: self.condition(),
+                then_stmt: self.statement(depth - 1),
+                else_stmt: self.statement(depth - 1),
+            }),
+            _ => Statement::WhileStmt(WhileStmt {
+                condition: self.condition(),
+                stmt: self.statement(depth - 1),
+            }),
+        });
+    }
+
+    fn condition(&mut self) -> Condition {
+        return Condition {
+            exp: self.boolean(self.config.expression_depth),
+            label: UNDEF,
+            span: Span::default(),
+        };
+    }
+
+    fn expression(&mut self) -> Box<Expression> {
+        if self.rng.chance(10) {
+            return Box::new(Expression::BooleanExpression(
+                self.boolean(self.config.expression_depth),
+            ));
+        }
+        return Box::new(Expression::ArithmeticExpression(
+            self.arithmetic(self.config.expression_depth),
+        ));
+    }
+
+    fn constant(&mut self) -> Integer {
+        if self.rng.chance(5) {
+            return *self.rng.choose(&[Integer::MIN, Integer::MAX]);
+        }
+        let (min, max) = (self.config.min_constant, self.config.max_constant);
+        if min > max {
+            return min;
+        }
+        let offset = self
+            .rng
+            .below((max.abs_diff(min) as usize).saturating_add(1));
+        return min.wrapping_add(offset as Integer);
+    }
+
+    pub fn arithmetic(&mut self, depth: usize) -> Box<ArithmeticExpression> {
+        if depth == 0 || self.config.arithmetic.is_empty() || self.rng.chance(40) {
+            if self.rng.chance(60) {
+                return Box::new(ArithmeticExpression::VarExp(VarExp { name: self.name() }));
+            }
+            return Box::new(ArithmeticExpression::NumExp(NumExp {
+                value: self.constant(),
+            }));
+        }
+
+        let operator = *self.rng.choose(&self.config.arithmetic);
+        let left = self.arithmetic(depth - 1);
+        let right = self.arithmetic(depth - 1);
+        return Box::new(match operator {
+            ArithmeticOperator::Add => ArithmeticExpression::AddExp(AddExp { left, right }),
+            ArithmeticOperator::Sub => ArithmeticExpression::SubExp(SubExp { left, right }),
+            ArithmeticOperator::Mul => ArithmeticExpression::MulExp(MulExp { left, right }),
+            ArithmeticOperator::Div => ArithmeticExpression::DivExp(DivExp { left, right }),
+        });
+    }
+
+    pub fn boolean(&mut self, depth: usize) -> Box<BooleanExpression> {
+        // Connectives need a level below them, relations and constants don't
+        let operators: Vec<BooleanOperator> = self
+            .config
+            .boolean
+            .iter()
+            .filter(|operator| depth > 0 || !operator.is_connective())
+            .copied()
+            .collect();
+        if operators.is_empty() {
+            return Box::new(BooleanExpression::CTrue(CTrue {}));
+        }
+
+        let below = depth.saturating_sub(1);
+        return Box::new(match *self.rng.choose(&operators) {
+            BooleanOperator::True => BooleanExpression::CTrue(CTrue {}),
+            BooleanOperator::False => BooleanExpression::CFalse(CFalse {}),
+            BooleanOperator::Not => BooleanExpression::NotExp(NotExp {
+                exp: self.boolean(below),
+            }),
+            BooleanOperator::And => BooleanExpression::AndExp(AndExp {
+                left: self.boolean(below),
+                right: self.boolean(below),
+            }),
+            BooleanOperator::Or => BooleanExpression::OrExp(OrExp {
+                left: self.boolean(below),
+                right: self.boolean(below),
+            }),
+            BooleanOperator::Eq => BooleanExpression::EqExp(EqExp {
+                left: self.arithmetic(below),
+                right: self.arithmetic(below),
+            }),
+            BooleanOperator::GT => BooleanExpression::GTExp(GTExp {
+                left: self.arithmetic(below),
+                right: self.arithmetic(below),
+            }),
+            BooleanOperator::LT => BooleanExpression::LTExp(LTExp {
+                left: self.arithmetic(below),
+                right: self.arithmetic(below),
+            }),
+            BooleanOperator::GEq => BooleanExpression::GEqExp(GEqExp {
+                left: self.arithmetic(below),
+                right: self.arithmetic(below),
+            }),
+            BooleanOperator::LEq => BooleanExpression::LEqExp(LEqExp {
+                left: self.arithmetic(below),
+                right: self.arithmetic(below),
+            }),
+        });
+    }
+}
+
+/// Program number `seed` for the given configuration
+pub fn generate(config: Config, seed: u64) -> Box<Program> {
+    return Generator::new(config, seed).program();
+}
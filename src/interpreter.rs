@@ -0,0 +1,291 @@
+use std::{collections::HashMap, fmt};
+
+use crate::abstract_syntax::{
+    AddExp, AndExp, ArithmeticExpression, AssignmentStmt, BooleanExpression, CFalse, CTrue,
+    Condition, DivExp, EqExp, Expression, GEqExp, GTExp, IfElseStmt, Integer, LEqExp, LTExp, Label,
+    MulExp, Name, NotExp, NumExp, OrExp, Program, SequenceStmt, SkipStmt, Statement, SubExp,
+    VarExp, WhileStmt,
+};
+
+/*
+   Small-step semantics of While after Nielson, Nielson & Hankin:
+
+   <[x := a]^l, s>                 -> s[x -> A[a]s]
+   <[skip]^l, s>                   -> s
+   <S1; S2, s>                     -> <S1'; S2, s'>  if <S1, s> -> <S1', s'>
+                                   -> <S2, s'>       if <S1, s> -> s'
+   <if [b]^l then S1 else S2, s>   -> <S1, s> if B[b]s, <S2, s> otherwise
+   <while [b]^l do S, s>           -> <S; while [b]^l do S, s> if B[b]s, s otherwise
+
+   Every step executes exactly one block, whose label is recorded. Arithmetic
+   wraps around on overflow; division by zero and reading a variable missing
+   from the store stop the execution. Both operands of `and` and `or` are
+   always evaluated, so every expression of a condition is computed whenever
+   the condition is.
+*/
+
+pub type Store = HashMap<Name, Integer>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Terminated,
+    StepLimit,
+    DivisionByZero { label: Label },
+    UnboundVariable { label: Label, name: Name },
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            Outcome::Terminated => write!(f, "terminated"),
+            Outcome::StepLimit => write!(f, "step limit reached"),
+            Outcome::DivisionByZero { label } => write!(f, "division by zero at label {}", label),
+            Outcome::UnboundVariable { label, name } => {
+                write!(f, "variable {} is not bound at label {}", name, label)
+            }
+        };
+    }
+}
+
+/// A run of a program: the labels of the blocks executed in order, the store
+/// after the last of them and why the run stopped
+#[derive(Debug, Clone)]
+pub struct Execution {
+    pub trace: Vec<Label>,
+    pub store: Store,
+    pub outcome: Outcome,
+}
+
+// Why evaluating an expression failed
+enum Fault {
+    DivisionByZero,
+    UnboundVariable(Name),
+}
+
+fn eval_arithmetic(exp: &ArithmeticExpression, store: &Store) -> Result<Integer, Fault> {
+    return match exp {
+        ArithmeticExpression::VarExp(VarExp { name }) => store
+            .get(name)
+            .copied()
+            .ok_or_else(|| Fault::UnboundVariable(name.clone())),
+        ArithmeticExpression::NumExp(NumExp { value }) => Ok(*value),
+        ArithmeticExpression::AddExp(AddExp { left, right }) => {
+            Ok(eval_arithmetic(left, store)?.wrapping_add(eval_arithmetic(right, store)?))
+        }
+        ArithmeticExpression::SubExp(SubExp { left, right }) => {
+            Ok(eval_arithmetic(left, store)?.wrapping_sub(eval_arithmetic(right, store)?))
+        }
+        ArithmeticExpression::MulExp(MulExp { left, right }) => {
+            Ok(eval_arithmetic(left, store)?.wrapping_mul(eval_arithmetic(right, store)?))
+        }
+        ArithmeticExpression::DivExp(DivExp { left, right }) => {
+            let n1 = eval_arithmetic(left, store)?;
+            let n2 = eval_arithmetic(right, store)?;
+            if n2 == 0 {
+                return Err(Fault::DivisionByZero);
+            }
+            Ok(n1.wrapping_div(n2))
+        }
+    };
+}
+
+fn eval_boolean(exp: &BooleanExpression, store: &Store) -> Result<bool, Fault> {
+    let relation = |left: &ArithmeticExpression,
+                    right: &ArithmeticExpression,
+                    holds: fn(Integer, Integer) -> bool| {
+        return Ok(holds(
+            eval_arithmetic(left, store)?,
+            eval_arithmetic(right, store)?,
+        ));
+    };
+
+    return match exp {
+        BooleanExpression::CTrue(CTrue {}) => Ok(true),
+        BooleanExpression::CFalse(CFalse {}) => Ok(false),
+        BooleanExpression::NotExp(NotExp { exp }) => Ok(!eval_boolean(exp, store)?),
+        BooleanExpression::AndExp(AndExp { left, right }) => {
+            let left = eval_boolean(left, store)?;
+            let right = eval_boolean(right, store)?;
+            Ok(left && right)
+        }
+        BooleanExpression::OrExp(OrExp { left, right }) => {
+            let left = eval_boolean(left, store)?;
+            let right = eval_boolean(right, store)?;
+            Ok(left || right)
+        }
+        BooleanExpression::EqExp(EqExp { left, right }) => relation(left, right, |n1, n2| n1 == n2),
+        BooleanExpression::GTExp(GTExp { left, right }) => relation(left, right, |n1, n2| n1 > n2),
+        BooleanExpression::LTExp(LTExp { left, right }) => relation(left, right, |n1, n2| n1 < n2),
+        BooleanExpression::GEqExp(GEqExp { left, right }) => {
+            relation(left, right, |n1, n2| n1 >= n2)
+        }
+        BooleanExpression::LEqExp(LEqExp { left, right }) => {
+            relation(left, right, |n1, n2| n1 <= n2)
+        }
+    };
+}
+
+// Booleans assigned to a variable are stored as 1 and 0
+fn eval_expression(exp: &Expression, store: &Store) -> Result<Integer, Fault> {
+    return match exp {
+        Expression::ArithmeticExpression(exp) => eval_arithmetic(exp, store),
+        Expression::BooleanExpression(exp) => Ok(eval_boolean(exp, store)? as Integer),
+    };
+}
+
+// Result of a single step: the remaining statement, if any, or the fault
+// raised by the block executed at `label`
+type Step = Result<Option<Box<Statement>>, (Label, Fault)>;
+
+fn step(stmt: Box<Statement>, store: &mut Store, trace: &mut Vec<Label>) -> Step {
+    return match *stmt {
+        Statement::AssignmentStmt(AssignmentStmt {
+            name,
+            exp,
+            label,
+            span: _,
+        }) => {
+            trace.push(label);
+            let value = eval_expression(&exp, store).map_err(|fault| (label, fault))?;
+            store.insert(name, value);
+            Ok(None)
+        }
+        Statement::SkipStmt(SkipStmt { label, span: _ }) => {
+            trace.push(label);
+            Ok(None)
+        }
+        Statement::SequenceStmt(SequenceStmt { s1, s2 }) => match step(s1, store, trace)? {
+            Some(s1) => Ok(Some(Box::new(Statement::SequenceStmt(SequenceStmt {
+                s1,
+                s2,
+            })))),
+            None => Ok(Some(s2)),
+        },
+        Statement::IfElseStmt(IfElseStmt {
+            condition,
+            then_stmt,
+            else_stmt,
+        }) => {
+            if test(&condition, store, trace)? {
+                Ok(Some(then_stmt))
+            } else {
+                Ok(Some(else_stmt))
+            }
+        }
+        Statement::WhileStmt(WhileStmt { condition, stmt }) => {
+            if test(&condition, store, trace)? {
+                let body = stmt.clone();
+                let again = Box::new(Statement::WhileStmt(WhileStmt { condition, stmt }));
+                Ok(Some(Box::new(Statement::SequenceStmt(SequenceStmt {
+                    s1: body,
+                    s2: again,
+                }))))
+            } else {
+                Ok(None)
+            }
+        }
+    };
+}
+
+fn test(
+    condition: &Condition,
+    store: &Store,
+    trace: &mut Vec<Label>,
+) -> Result<bool, (Label, Fault)> {
+    trace.push(condition.label);
+    return eval_boolean(&condition.exp, store).map_err(|fault| (condition.label, fault));
+}
+
+/// Runs `program` from `store` for at most `step_limit` steps
+pub fn run(program: Box<Program>, store: Store, step_limit: usize) -> Execution {
+    let mut execution = Execution {
+        trace: Vec::new(),
+        store,
+        outcome: Outcome::StepLimit,
+    };
+
+    let mut current = Some(program);
+    while let Some(stmt) = current {
+        if execution.trace.len() == step_limit {
+            return execution;
+        }
+
+        current = match step(stmt, &mut execution.store, &mut execution.trace) {
+            Ok(next) => next,
+            Err((label, fault)) => {
+                execution.outcome = match fault {
+                    Fault::DivisionByZero => Outcome::DivisionByZero { label },
+                    Fault::UnboundVariable(name) => Outcome::UnboundVariable { label, name },
+                };
+                return execution;
+            }
+        };
+    }
+
+    execution.outcome = Outcome::Terminated;
+    return execution;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    fn execute(source: &str, store: Store) -> Execution {
+        return run(parse(source).unwrap(), store, 100);
+    }
+
+    #[test]
+    fn terminates_with_the_final_store() {
+        let execution = execute(
+            "x := 3; y := 1; while x > 1 do (y := y * x; x := x - 1)",
+            Store::new(),
+        );
+        assert_eq!(execution.outcome, Outcome::Terminated);
+        assert_eq!(execution.store["y"], 6);
+        assert_eq!(execution.trace[..3], [1, 2, 3]);
+    }
+
+    #[test]
+    fn division_by_zero_stops_the_run() {
+        let execution = execute("x := 1 / 0; y := 2", Store::new());
+        assert_eq!(execution.outcome, Outcome::DivisionByZero { label: 1 });
+        // The block that faulted is the last one traced, its assignment is not made
+        assert_eq!(execution.trace, Vec::from([1]));
+        assert!(execution.store.is_empty());
+    }
+
+    #[test]
+    fn arithmetic_wraps_around() {
+        let execution = execute(
+            "x := 9223372036854775807; x := x + 1; y := x * 2",
+            Store::new(),
+        );
+        assert_eq!(execution.outcome, Outcome::Terminated);
+        assert_eq!(execution.store["x"], Integer::MIN);
+        assert_eq!(execution.store["y"], 0);
+    }
+
+    #[test]
+    fn endless_loops_hit_the_step_limit() {
+        let execution = execute("while true do skip", Store::new());
+        assert_eq!(execution.outcome, Outcome::StepLimit);
+        assert_eq!(execution.trace.len(), 100);
+    }
+
+    #[test]
+    fn reading_an_unbound_variable_stops_the_run() {
+        let execution = execute("x := 1; y := x + z", Store::new());
+        assert_eq!(
+            execution.outcome,
+            Outcome::UnboundVariable {
+                label: 2,
+                name: String::from("z"),
+            }
+        );
+
+        let execution = execute("x := 1; y := x + z", Store::from([(String::from("z"), 2)]));
+        assert_eq!(execution.outcome, Outcome::Terminated);
+        assert_eq!(execution.store["y"], 3);
+    }
+}
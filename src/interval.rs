@@ -0,0 +1,526 @@
+use std::ops::{Add, Div, Mul, Sub};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    abstract_syntax::Integer,
+    domain::{Relation, ValueAnalysis, ValueDomain},
+    framework::L,
+    lattice::Lattice,
+};
+
+/*
+   Intervals of Integer values. The interpreter wraps around on overflow, so
+   an operation that may leave the range of Integer can produce any value and
+   its result is ⊤. An infinite bound only says that values may be as small
+   as Integer::MIN or as large as Integer::MAX, and overflow is checked with
+   those extremes: [0, +∞] + 1 is ⊤, as Integer::MAX + 1 wraps around.
+*/
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Bound {
+    NegInf,
+    Finite(Integer),
+    PosInf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Interval {
+    Empty,
+    Range(Bound, Bound), // Invariant: low <= high, low != PosInf, high != NegInf
+}
+
+impl Bound {
+    /// The most extreme value the bound admits at run time
+    fn extreme(self) -> i128 {
+        return match self {
+            Bound::NegInf => Integer::MIN as i128,
+            Bound::Finite(n) => n as i128,
+            Bound::PosInf => Integer::MAX as i128,
+        };
+    }
+
+    /// The bound for `value`, infinite in the direction `infinite` points
+    /// (-1, 0 for none or 1) and None when the value overflowed
+    fn from_wide(value: i128, infinite: i128) -> Option<Bound> {
+        if value < Integer::MIN as i128 || value > Integer::MAX as i128 {
+            return None;
+        }
+        if infinite != 0 {
+            return Some(Bound::infinity(infinite));
+        }
+        return Some(Bound::Finite(value as Integer));
+    }
+
+    fn is_infinite(self) -> bool {
+        return !matches!(self, Bound::Finite(_));
+    }
+
+    fn signum(&self) -> i128 {
+        return match self {
+            Bound::NegInf => -1,
+            Bound::Finite(n) => n.signum() as i128,
+            Bound::PosInf => 1,
+        };
+    }
+
+    fn infinity(sign: i128) -> Bound {
+        if sign < 0 {
+            return Bound::NegInf;
+        }
+        return Bound::PosInf;
+    }
+
+    /// Only called on bounds that never add up to -inf + +inf
+    fn add(self, other: Bound) -> Option<Bound> {
+        let infinite = match (self, other) {
+            (Bound::NegInf, _) | (_, Bound::NegInf) => -1,
+            (Bound::PosInf, _) | (_, Bound::PosInf) => 1,
+            _ => 0,
+        };
+        return Bound::from_wide(self.extreme() + other.extreme(), infinite);
+    }
+
+    /// Only called on bounds that never subtract an infinity from itself
+    fn sub(self, other: Bound) -> Option<Bound> {
+        let infinite = match (self, other) {
+            (Bound::NegInf, _) | (_, Bound::PosInf) => -1,
+            (Bound::PosInf, _) | (_, Bound::NegInf) => 1,
+            _ => 0,
+        };
+        return Bound::from_wide(self.extreme() - other.extreme(), infinite);
+    }
+
+    fn mul(self, other: Bound) -> Option<Bound> {
+        let product = self.extreme() * other.extreme();
+        if product == 0 {
+            return Some(Bound::Finite(0));
+        }
+        let infinite = if self.is_infinite() || other.is_infinite() {
+            self.signum() * other.signum()
+        } else {
+            0
+        };
+        return Bound::from_wide(product, infinite);
+    }
+
+    /// Truncating division by a non-zero bound. A finite bound divided by an
+    /// infinite one is the quotient of the extremes, -1, 0 or 1.
+    fn div(self, other: Bound) -> Option<Bound> {
+        let infinite = if self.is_infinite() && !other.is_infinite() {
+            self.signum() * other.signum()
+        } else {
+            0
+        };
+        return Bound::from_wide(self.extreme() / other.extreme(), infinite);
+    }
+
+    /// Used for comparisons rather than arithmetic, so a bound moved past
+    /// the range of Integer becomes infinite instead of overflowing
+    fn increment(self, by: Integer) -> Bound {
+        return self
+            .add(Bound::Finite(by))
+            .unwrap_or(Bound::infinity(by as i128));
+    }
+}
+
+impl Interval {
+    pub fn constant(value: Integer) -> Interval {
+        return Interval::Range(Bound::Finite(value), Bound::Finite(value));
+    }
+
+    pub fn new(low: Bound, high: Bound) -> Interval {
+        if low > high || low == Bound::PosInf || high == Bound::NegInf {
+            return Interval::Empty;
+        }
+        return Interval::Range(low, high);
+    }
+
+    /// ⊤ when a bound overflowed
+    fn from_bounds(low: Option<Bound>, high: Option<Bound>) -> Interval {
+        return match (low, high) {
+            (Some(low), Some(high)) => Interval::new(low, high),
+            _ => IntervalLattice {}.top(),
+        };
+    }
+
+    fn from_corners(corners: [Option<Bound>; 4]) -> Interval {
+        if corners.contains(&None) {
+            return IntervalLattice {}.top();
+        }
+        let corners = corners.map(Option::unwrap);
+        let low = *corners.iter().min().unwrap();
+        let high = *corners.iter().max().unwrap();
+        return Interval::new(low, high);
+    }
+}
+
+impl L for Bound {
+    fn key(&self) -> String {
+        return match self {
+            Bound::NegInf => String::from("-∞"),
+            Bound::Finite(n) => n.to_string(),
+            Bound::PosInf => String::from("+∞"),
+        };
+    }
+}
+
+/// `[low, high]`, ⊥ for the empty interval
+impl L for Interval {
+    fn key(&self) -> String {
+        return match self {
+            Interval::Empty => String::from("⊥"),
+            Interval::Range(low, high) => format!("[{}, {}]", low.key(), high.key()),
+        };
+    }
+}
+
+impl Add for Interval {
+    type Output = Interval;
+
+    fn add(self, other: Interval) -> Interval {
+        return match (self, other) {
+            (Interval::Range(l1, h1), Interval::Range(l2, h2)) => {
+                Interval::from_bounds(l1.add(l2), h1.add(h2))
+            }
+            _ => Interval::Empty,
+        };
+    }
+}
+
+impl Sub for Interval {
+    type Output = Interval;
+
+    fn sub(self, other: Interval) -> Interval {
+        return match (self, other) {
+            (Interval::Range(l1, h1), Interval::Range(l2, h2)) => {
+                Interval::from_bounds(l1.sub(h2), h1.sub(l2))
+            }
+            _ => Interval::Empty,
+        };
+    }
+}
+
+impl Mul for Interval {
+    type Output = Interval;
+
+    fn mul(self, other: Interval) -> Interval {
+        return match (self, other) {
+            (Interval::Range(l1, h1), Interval::Range(l2, h2)) => {
+                Interval::from_corners([l1.mul(l2), l1.mul(h2), h1.mul(l2), h1.mul(h2)])
+            }
+            _ => Interval::Empty,
+        };
+    }
+}
+
+impl Div for Interval {
+    type Output = Interval;
+
+    /// Division by zero has no result, so zero is removed from the divisor by
+    /// splitting it into its negative and positive parts
+    fn div(self, other: Interval) -> Interval {
+        let (l1, h1) = match self {
+            Interval::Range(l1, h1) => (l1, h1),
+            Interval::Empty => return Interval::Empty,
+        };
+
+        let lattice = IntervalLattice {};
+        let negative = lattice.meet(other, Interval::new(Bound::NegInf, Bound::Finite(-1)));
+        let positive = lattice.meet(other, Interval::new(Bound::Finite(1), Bound::PosInf));
+
+        let mut result = Interval::Empty;
+        for divisor in [negative, positive] {
+            if let Interval::Range(l2, h2) = divisor {
+                result = lattice.join(
+                    result,
+                    Interval::from_corners([l1.div(l2), l1.div(h2), h1.div(l2), h1.div(h2)]),
+                );
+            }
+        }
+        return result;
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct IntervalLattice {}
+
+impl Lattice for IntervalLattice {
+    type Element = Interval;
+
+    fn bottom(&self) -> Interval {
+        Interval::Empty
+    }
+
+    fn top(&self) -> Interval {
+        Interval::Range(Bound::NegInf, Bound::PosInf)
+    }
+
+    fn join(&self, i1: Interval, i2: Interval) -> Interval {
+        return match (i1, i2) {
+            (Interval::Empty, i) | (i, Interval::Empty) => i,
+            (Interval::Range(l1, h1), Interval::Range(l2, h2)) => {
+                Interval::Range(l1.min(l2), h1.max(h2))
+            }
+        };
+    }
+
+    fn meet(&self, i1: Interval, i2: Interval) -> Interval {
+        return match (i1, i2) {
+            (Interval::Empty, _) | (_, Interval::Empty) => Interval::Empty,
+            (Interval::Range(l1, h1), Interval::Range(l2, h2)) => {
+                Interval::new(l1.max(l2), h1.min(h2))
+            }
+        };
+    }
+
+    fn leq(&self, i1: Interval, i2: Interval) -> bool {
+        return match (i1, i2) {
+            (Interval::Empty, _) => true,
+            (_, Interval::Empty) => false,
+            (Interval::Range(l1, h1), Interval::Range(l2, h2)) => l2 <= l1 && h1 <= h2,
+        };
+    }
+
+    /// Unstable bounds jump straight to infinity
+    fn widen(&self, i1: Interval, i2: Interval) -> Interval {
+        return match (i1, i2) {
+            (Interval::Empty, i) | (i, Interval::Empty) => i,
+            (Interval::Range(l1, h1), Interval::Range(l2, h2)) => Interval::Range(
+                if l2 < l1 { Bound::NegInf } else { l1 },
+                if h2 > h1 { Bound::PosInf } else { h1 },
+            ),
+        };
+    }
+
+    /// Only infinite bounds are refined
+    fn narrow(&self, i1: Interval, i2: Interval) -> Interval {
+        return match (i1, i2) {
+            (Interval::Empty, _) | (_, Interval::Empty) => Interval::Empty,
+            (Interval::Range(l1, h1), Interval::Range(l2, h2)) => Interval::new(
+                if l1 == Bound::NegInf { l2 } else { l1 },
+                if h1 == Bound::PosInf { h2 } else { h1 },
+            ),
+        };
+    }
+}
+
+impl ValueDomain for IntervalLattice {
+    fn constant(&self, value: Integer) -> Interval {
+        return Interval::constant(value);
+    }
+
+    fn add(&self, i1: Interval, i2: Interval) -> Interval {
+        return i1 + i2;
+    }
+
+    fn sub(&self, i1: Interval, i2: Interval) -> Interval {
+        return i1 - i2;
+    }
+
+    fn mul(&self, i1: Interval, i2: Interval) -> Interval {
+        return i1 * i2;
+    }
+
+    fn div(&self, i1: Interval, i2: Interval) -> Interval {
+        return i1 / i2;
+    }
+
+    fn restrict(&self, relation: Relation, other: Interval) -> Interval {
+        let (low, high) = match other {
+            Interval::Range(low, high) => (low, high),
+            Interval::Empty => return Interval::Empty,
+        };
+
+        return match relation {
+            Relation::Eq => other,
+            Relation::NEq => self.top(),
+            Relation::GT => Interval::new(low.increment(1), Bound::PosInf),
+            Relation::GEq => Interval::new(low, Bound::PosInf),
+            Relation::LT => Interval::new(Bound::NegInf, high.increment(-1)),
+            Relation::LEq => Interval::new(Bound::NegInf, high),
+        };
+    }
+
+    fn satisfiable(&self, relation: Relation, left: Interval, right: Interval) -> bool {
+        let ((l1, h1), (l2, h2)) = match (left, right) {
+            (Interval::Range(l1, h1), Interval::Range(l2, h2)) => ((l1, h1), (l2, h2)),
+            _ => return false,
+        };
+
+        return match relation {
+            Relation::Eq => l1 <= h2 && l2 <= h1,
+            Relation::NEq => !(l1 == h1 && l2 == h2 && l1 == l2),
+            Relation::GT => h1 > l2,
+            Relation::GEq => h1 >= l2,
+            Relation::LT => l1 < h2,
+            Relation::LEq => l1 <= h2,
+        };
+    }
+}
+
+pub type IntervalAnalysis = ValueAnalysis<IntervalLattice>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        framework::solve,
+        interpreter::{run, Store},
+        parser::parse,
+    };
+
+    fn range(low: Integer, high: Integer) -> Interval {
+        return Interval::new(Bound::Finite(low), Bound::Finite(high));
+    }
+
+    #[test]
+    fn overflow_goes_to_top() {
+        let top = IntervalLattice {}.top();
+        assert_eq!(
+            Interval::constant(Integer::MAX) + Interval::constant(1),
+            top
+        );
+        assert_eq!(
+            Interval::constant(0) - Interval::constant(Integer::MIN),
+            top
+        );
+        assert_eq!(range(2, Integer::MAX) * range(2, 3), top);
+        assert_eq!(
+            Interval::constant(Integer::MIN) / Interval::constant(-1),
+            top
+        );
+    }
+
+    #[test]
+    fn overflow_through_infinite_bounds_goes_to_top() {
+        let top = IntervalLattice {}.top();
+        let up = Interval::new(Bound::Finite(0), Bound::PosInf);
+        let down = Interval::new(Bound::NegInf, Bound::Finite(0));
+        assert_eq!(up + Interval::constant(1), top);
+        assert_eq!(down - Interval::constant(1), top);
+        assert_eq!(down * Interval::constant(-1), top);
+        assert_eq!(down / Interval::constant(-1), top);
+
+        // Moving away from the infinite end cannot overflow
+        assert_eq!(
+            up - Interval::constant(1),
+            Interval::new(Bound::Finite(-1), Bound::PosInf)
+        );
+        assert_eq!(
+            down + Interval::constant(1),
+            Interval::new(Bound::NegInf, Bound::Finite(1))
+        );
+        assert_eq!(
+            up * Interval::constant(-1),
+            Interval::new(Bound::NegInf, Bound::Finite(0))
+        );
+    }
+
+    // Runs the program and checks that the final store lies within the
+    // result at the exit of the last block executed
+    fn assert_run_within_result(source: &str) {
+        let program = parse(source).unwrap();
+        let execution = run(program.clone(), Store::new(), 100);
+        let result = solve(Box::new(IntervalAnalysis::new(
+            program,
+            IntervalLattice {},
+            2,
+        )));
+
+        let lattice = IntervalLattice {};
+        let last = execution.trace.last().unwrap();
+        for (name, value) in execution.store {
+            let interval = result.exit[last][&name];
+            assert!(
+                lattice.leq(Interval::constant(value), interval),
+                "{} = {} not in {} at label {}",
+                name,
+                value,
+                interval.key(),
+                last
+            );
+        }
+    }
+
+    #[test]
+    fn overflowing_program_keeps_the_wrapped_values() {
+        assert_run_within_result("x := 9223372036854775807; x := x + 1; y := x");
+    }
+
+    #[test]
+    fn loop_that_overflows_past_a_widened_bound() {
+        // The loop exits once x wraps around to Integer::MIN
+        assert_run_within_result("x := 9223372036854775806; while x >= 0 do x := x + 1; y := x");
+    }
+
+    #[test]
+    fn widen_sends_unstable_bounds_to_infinity() {
+        let lattice = IntervalLattice {};
+        assert_eq!(
+            lattice.widen(range(0, 1), range(0, 2)),
+            Interval::new(Bound::Finite(0), Bound::PosInf)
+        );
+        assert_eq!(
+            lattice.widen(range(0, 1), range(-1, 1)),
+            Interval::new(Bound::NegInf, Bound::Finite(1))
+        );
+        assert_eq!(lattice.widen(range(0, 5), range(1, 4)), range(0, 5));
+        assert_eq!(lattice.widen(Interval::Empty, range(1, 4)), range(1, 4));
+    }
+
+    #[test]
+    fn narrow_refines_only_infinite_bounds() {
+        let lattice = IntervalLattice {};
+        assert_eq!(
+            lattice.narrow(Interval::new(Bound::Finite(0), Bound::PosInf), range(0, 10)),
+            range(0, 10)
+        );
+        assert_eq!(lattice.narrow(range(0, 20), range(5, 10)), range(0, 20));
+        assert_eq!(
+            lattice.narrow(range(0, 20), Interval::Empty),
+            Interval::Empty
+        );
+    }
+
+    #[test]
+    fn division_skips_a_zero_divisor() {
+        assert_eq!(range(10, 20) / range(-2, 2), range(-20, 20));
+        assert_eq!(range(10, 20) / range(0, 5), range(2, 20));
+        assert_eq!(range(10, 20) / Interval::constant(0), Interval::Empty);
+        assert_eq!(
+            range(10, 20) / Interval::new(Bound::Finite(1), Bound::PosInf),
+            range(0, 20)
+        );
+    }
+
+    #[test]
+    fn restrict_keeps_values_satisfying_the_relation() {
+        let lattice = IntervalLattice {};
+        let other = range(3, 7);
+        assert_eq!(lattice.restrict(Relation::Eq, other), other);
+        assert_eq!(lattice.restrict(Relation::NEq, other), lattice.top());
+        assert_eq!(
+            lattice.restrict(Relation::GT, other),
+            Interval::new(Bound::Finite(4), Bound::PosInf)
+        );
+        assert_eq!(
+            lattice.restrict(Relation::GEq, other),
+            Interval::new(Bound::Finite(3), Bound::PosInf)
+        );
+        assert_eq!(
+            lattice.restrict(Relation::LT, other),
+            Interval::new(Bound::NegInf, Bound::Finite(6))
+        );
+        assert_eq!(
+            lattice.restrict(Relation::LEq, other),
+            Interval::new(Bound::NegInf, Bound::Finite(7))
+        );
+
+        // Nothing lies beyond the range of Integer
+        let max = Interval::constant(Integer::MAX);
+        let min = Interval::constant(Integer::MIN);
+        assert_eq!(lattice.restrict(Relation::GT, max), Interval::Empty);
+        assert_eq!(lattice.restrict(Relation::LT, min), Interval::Empty);
+    }
+}
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{
+    abstract_syntax::{Label, Program},
+    framework::{compare_keys, AnalysisResult},
+};
+
+/*
+   JSON exchange format. Programs use the externally tagged representation of
+   the abstract syntax, e.g. the block [x := 1]^1 is
+
+   {"AssignmentStmt": {"name": "x",
+                       "exp": {"ArithmeticExpression": {"NumExp": {"value": 1}}},
+                       "label": 1,
+                       "span": {"start": 0, "end": 6, "line": 1, "column": 1}}}
+
+   Spans may be left out and read back as unknown. Results are a list of
+   {"label": l, "entry": ..., "exit": ...} objects ordered by label, and the
+   results of several analyses an object from their names to these lists,
+   e.g. {"lv": [...], "rd": [...]}. Maps are
+   written with their keys sorted and sets as arrays sorted like the keys of
+   `framework::L`, so the same result always gives the same text.
+*/
+
+pub fn program_to_json(program: &Program) -> String {
+    return serde_json::to_string_pretty(program).unwrap();
+}
+
+/// Reads a program of any depth: every `;` nests the rest of a sequence one
+/// level deeper, so the recursion limit of serde_json is lifted and the stack
+/// grown on demand instead
+pub fn program_from_json(json: &str) -> Result<Box<Program>, serde_json::Error> {
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    deserializer.disable_recursion_limit();
+    let program = Box::<Program>::deserialize(serde_stacker::Deserializer::new(&mut deserializer))?;
+    deserializer.end()?;
+    return Ok(program);
+}
+
+#[derive(Serialize)]
+struct LabelResult {
+    label: Label,
+    entry: Value,
+    exit: Value,
+}
+
+// The element as JSON with the elements of a set sorted, objects of `Value`
+// keep their keys sorted already
+fn sorted<E: Serialize>(element: &E) -> Value {
+    let mut value = serde_json::to_value(element).unwrap();
+    if let Value::Array(elements) = &mut value {
+        elements.sort_by(|e1, e2| compare_keys(&e1.to_string(), &e2.to_string()));
+    }
+    return value;
+}
+
+pub fn result_to_value<E: Serialize>(result: &AnalysisResult<E>) -> Value {
+    let mut labels: Vec<&Label> = result.entry.keys().collect();
+    labels.sort();
+
+    let rows: Vec<LabelResult> = labels
+        .into_iter()
+        .map(|l| LabelResult {
+            label: *l,
+            entry: sorted(&result.entry[l]),
+            exit: sorted(&result.exit[l]),
+        })
+        .collect();
+
+    return serde_json::to_value(rows).unwrap();
+}
+
+pub fn result_to_json<E: Serialize>(result: &AnalysisResult<E>) -> String {
+    return serde_json::to_string_pretty(&result_to_value(result)).unwrap();
+}
+
+/// The results of several analyses as one object keyed by analysis name
+pub fn results_to_json(results: Vec<(&str, Value)>) -> String {
+    let object: Map<String, Value> = results
+        .into_iter()
+        .map(|(name, result)| (name.to_string(), result))
+        .collect();
+    return serde_json::to_string_pretty(&object).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    #[test]
+    fn long_sequences_round_trip() {
+        let source = vec!["x := x + 1"; 200].join("; ");
+        let program = parse(&source).unwrap();
+        let json = program_to_json(&program);
+
+        let read = program_from_json(&json).unwrap();
+        assert_eq!(program_to_json(&read), json);
+        assert_eq!(read.to_string(), program.to_string());
+    }
+
+    #[test]
+    fn trailing_input_is_rejected() {
+        let json = program_to_json(&parse("skip").unwrap());
+        assert!(program_from_json(&(json + " {}")).is_err());
+    }
+}
@@ -0,0 +1,352 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    abstract_syntax::{
+        AssignmentStmt, Condition, IfElseStmt, Label, Program, SequenceStmt, SkipStmt, Span,
+        Statement, WhileStmt, UNDEF,
+    },
+    diagnostics::Diagnostic,
+    utils::{blocks, label, span},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LabelError {
+    Duplicate {
+        label: Label,
+        spans: Vec<Span>,
+    },
+    Undefined {
+        span: Span,
+    },
+    NonPositive {
+        label: Label,
+        span: Span,
+    },
+    OutOfRange {
+        label: Label,
+        span: Span,
+        blocks: usize,
+    },
+    Missing {
+        label: Label,
+    },
+}
+
+impl fmt::Display for LabelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            LabelError::Duplicate { label, spans } => {
+                write!(f, "label {} is used by {} blocks", label, spans.len())
+            }
+            LabelError::Undefined { span: _ } => {
+                write!(f, "block has the reserved label {} (UNDEF)", UNDEF)
+            }
+            LabelError::NonPositive { label, span: _ } => {
+                write!(f, "label {} is not positive", label)
+            }
+            LabelError::OutOfRange {
+                label,
+                span: _,
+                blocks,
+            } => {
+                write!(f, "label {} is out of range 1..={}", label, blocks)
+            }
+            LabelError::Missing { label } => {
+                write!(f, "label {} is missing from the numbering", label)
+            }
+        };
+    }
+}
+
+impl LabelError {
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        return match self {
+            LabelError::Duplicate { label, spans } => Diagnostic {
+                label: Some(*label),
+                ..Diagnostic::error(self.to_string(), spans[spans.len() - 1])
+            },
+            LabelError::Undefined { span } => Diagnostic::error(self.to_string(), *span),
+            LabelError::NonPositive { label, span }
+            | LabelError::OutOfRange {
+                label,
+                span,
+                blocks: _,
+            } => Diagnostic {
+                label: Some(*label),
+                ..Diagnostic::error(self.to_string(), *span)
+            },
+            LabelError::Missing { label: _ } => {
+                Diagnostic::error(self.to_string(), Span::default())
+            }
+        };
+    }
+}
+
+/// Numbers the blocks of a statement 1, 2, ... in the order `blocks` lists
+/// them, which is the order used throughout Nielson, Nielson & Hankin.
+pub fn relabel(stmt: Box<Statement>) -> Box<Statement> {
+    let mut next: Label = 1;
+    return relabel_from(stmt, &mut next);
+}
+
+fn relabel_from(stmt: Box<Statement>, next: &mut Label) -> Box<Statement> {
+    let mut fresh = || {
+        let l = *next;
+        *next += 1;
+        l
+    };
+
+    return Box::new(match *stmt {
+        Statement::AssignmentStmt(AssignmentStmt {
+            name,
+            exp,
+            label: _,
+            span,
+        }) => Statement::AssignmentStmt(AssignmentStmt {
+            name,
+            exp,
+            label: fresh(),
+            span,
+        }),
+        Statement::SkipStmt(SkipStmt { label: _, span }) => Statement::SkipStmt(SkipStmt {
+            label: fresh(),
+            span,
+        }),
+        Statement::SequenceStmt(SequenceStmt { s1, s2 }) => {
+            let s1 = relabel_from(s1, next);
+            let s2 = relabel_from(s2, next);
+            Statement::SequenceStmt(SequenceStmt { s1, s2 })
+        }
+        Statement::IfElseStmt(IfElseStmt {
+            condition:
+                Condition {
+                    exp,
+                    label: _,
+                    span,
+                },
+            then_stmt,
+            else_stmt,
+        }) => {
+            let condition = Condition {
+                exp,
+                label: fresh(),
+                span,
+            };
+            let then_stmt = relabel_from(then_stmt, next);
+            let else_stmt = relabel_from(else_stmt, next);
+            Statement::IfElseStmt(IfElseStmt {
+                condition,
+                then_stmt,
+                else_stmt,
+            })
+        }
+        Statement::WhileStmt(WhileStmt {
+            condition:
+                Condition {
+                    exp,
+                    label: _,
+                    span,
+                },
+            stmt,
+        }) => {
+            let condition = Condition {
+                exp,
+                label: fresh(),
+                span,
+            };
+            let stmt = relabel_from(stmt, next);
+            Statement::WhileStmt(WhileStmt { condition, stmt })
+        }
+    });
+}
+
+/// Checks that every block carries a distinct positive label and that the
+/// labels of the n blocks form the range 1..=n without gaps. `framework::solve`
+/// indexes blocks by label, so duplicates would silently merge their analysis
+/// entries.
+pub fn validate_labels(program: Box<Program>) -> Result<(), Vec<LabelError>> {
+    let mut errors = Vec::new();
+    let mut spans_by_label: HashMap<Label, Vec<Span>> = HashMap::new();
+    let mut order = Vec::new();
+
+    let program_blocks = blocks(program);
+    let count = program_blocks.len();
+    for block in program_blocks {
+        let l = label(block.clone());
+        let s = span(block);
+
+        if l == UNDEF {
+            errors.push(LabelError::Undefined { span: s });
+        } else if l <= 0 {
+            errors.push(LabelError::NonPositive { label: l, span: s });
+        } else {
+            if !spans_by_label.contains_key(&l) {
+                order.push(l);
+            }
+            spans_by_label.entry(l).or_default().push(s);
+        }
+    }
+
+    for l in &order {
+        let spans = &spans_by_label[l];
+        if spans.len() > 1 {
+            errors.push(LabelError::Duplicate {
+                label: *l,
+                spans: spans.clone(),
+            });
+        }
+        if *l > count as Label {
+            errors.push(LabelError::OutOfRange {
+                label: *l,
+                span: spans[0],
+                blocks: count,
+            });
+        }
+    }
+
+    // Only up to the number of blocks, a label far out of range has been
+    // reported above and must not make this loop run up to it
+    for l in 1..=count as Label {
+        if !spans_by_label.contains_key(&l) {
+            errors.push(LabelError::Missing { label: l });
+        }
+    }
+
+    if errors.is_empty() {
+        return Ok(());
+    }
+    return Err(errors);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        abstract_syntax::{ArithmeticExpression, Expression, NumExp},
+        parser::parse,
+    };
+
+    // `x := 1; skip; y := 2` with the given labels
+    fn program(labels: [Label; 3]) -> Box<Program> {
+        let assignment = |name: &str, label| {
+            Box::new(Statement::AssignmentStmt(AssignmentStmt {
+                name: name.to_string(),
+                exp: Box::new(Expression::ArithmeticExpression(Box::new(
+                    ArithmeticExpression::NumExp(NumExp { value: 1 }),
+                ))),
+                label,
+                span: Span::default(),
+            }))
+        };
+        return Box::new(Statement::SequenceStmt(SequenceStmt {
+            s1: assignment("x", labels[0]),
+            s2: Box::new(Statement::SequenceStmt(SequenceStmt {
+                s1: Box::new(Statement::SkipStmt(SkipStmt {
+                    label: labels[1],
+                    span: Span::default(),
+                })),
+                s2: assignment("y", labels[2]),
+            })),
+        }));
+    }
+
+    #[test]
+    fn parsed_programs_are_valid() {
+        let program = parse("x := 1; while x < 3 do x := x + 1").unwrap();
+        assert_eq!(validate_labels(program), Ok(()));
+    }
+
+    #[test]
+    fn duplicate_labels() {
+        let errors = validate_labels(program([1, 2, 1])).unwrap_err();
+        assert_eq!(
+            errors,
+            Vec::from([
+                LabelError::Duplicate {
+                    label: 1,
+                    spans: Vec::from([Span::default(), Span::default()]),
+                },
+                LabelError::Missing { label: 3 },
+            ])
+        );
+    }
+
+    #[test]
+    fn missing_labels() {
+        let errors = validate_labels(program([1, 2, 4])).unwrap_err();
+        assert_eq!(
+            errors,
+            Vec::from([
+                LabelError::OutOfRange {
+                    label: 4,
+                    span: Span::default(),
+                    blocks: 3,
+                },
+                LabelError::Missing { label: 3 },
+            ])
+        );
+    }
+
+    #[test]
+    fn large_labels_are_reported_once() {
+        let errors = validate_labels(program([1, Label::MAX, Label::MAX])).unwrap_err();
+        assert_eq!(
+            errors,
+            Vec::from([
+                LabelError::Duplicate {
+                    label: Label::MAX,
+                    spans: Vec::from([Span::default(), Span::default()]),
+                },
+                LabelError::OutOfRange {
+                    label: Label::MAX,
+                    span: Span::default(),
+                    blocks: 3,
+                },
+                LabelError::Missing { label: 2 },
+                LabelError::Missing { label: 3 },
+            ])
+        );
+    }
+
+    #[test]
+    fn undefined_labels() {
+        let errors = validate_labels(program([1, UNDEF, 2])).unwrap_err();
+        assert_eq!(
+            errors,
+            Vec::from([
+                LabelError::Undefined {
+                    span: Span::default()
+                },
+                LabelError::Missing { label: 3 },
+            ])
+        );
+    }
+
+    #[test]
+    fn labels_must_be_positive() {
+        // Labelled 0..n the numbering starts too early and stops short
+        let errors = validate_labels(program([0, 1, 2])).unwrap_err();
+        assert_eq!(
+            errors,
+            Vec::from([
+                LabelError::NonPositive {
+                    label: 0,
+                    span: Span::default(),
+                },
+                LabelError::Missing { label: 3 },
+            ])
+        );
+
+        let errors = validate_labels(program([1, 2, -3])).unwrap_err();
+        assert_eq!(
+            errors,
+            Vec::from([
+                LabelError::NonPositive {
+                    label: -3,
+                    span: Span::default(),
+                },
+                LabelError::Missing { label: 3 },
+            ])
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    hash::Hash,
+};
+
+use crate::{
+    abstract_syntax::Name,
+    utils::{intersection, union},
+};
+
+/// A complete lattice of analysis properties. Lattices are values rather than
+/// bare element types so that instances can depend on the analysed program,
+/// e.g. a powerset lattice over the expressions occurring in it.
+pub trait Lattice {
+    type Element: Clone + Debug + PartialEq;
+
+    fn bottom(&self) -> Self::Element;
+    fn top(&self) -> Self::Element;
+
+    /// Least upper bound, used to combine information flowing into a label
+    fn join(&self, e1: Self::Element, e2: Self::Element) -> Self::Element;
+
+    /// Greatest lower bound
+    fn meet(&self, e1: Self::Element, e2: Self::Element) -> Self::Element;
+
+    /// Partial order, e1 is at most as informative as e2
+    fn leq(&self, e1: Self::Element, e2: Self::Element) -> bool;
+
+    /// Widening operator, lattices of finite height can keep the default
+    fn widen(&self, e1: Self::Element, e2: Self::Element) -> Self::Element {
+        return self.join(e1, e2);
+    }
+
+    /// Narrowing operator, refines e1 by a smaller e2 without descending
+    /// forever; for lattices of finite height the meet will do
+    fn narrow(&self, e1: Self::Element, e2: Self::Element) -> Self::Element {
+        return self.meet(e1, e2);
+    }
+}
+
+/// Subsets of `universe` ordered by inclusion, for may analyses such as
+/// reaching definitions and live variables
+#[derive(Debug, Clone)]
+pub struct PowersetLattice<T> {
+    pub universe: HashSet<T>,
+}
+
+impl<T: Eq + Hash + Clone + Debug> Lattice for PowersetLattice<T> {
+    type Element = HashSet<T>;
+
+    fn bottom(&self) -> HashSet<T> {
+        HashSet::new()
+    }
+
+    fn top(&self) -> HashSet<T> {
+        self.universe.clone()
+    }
+
+    fn join(&self, set1: HashSet<T>, set2: HashSet<T>) -> HashSet<T> {
+        return union(set1, set2);
+    }
+
+    fn meet(&self, set1: HashSet<T>, set2: HashSet<T>) -> HashSet<T> {
+        return intersection(set1, set2);
+    }
+
+    fn leq(&self, set1: HashSet<T>, set2: HashSet<T>) -> bool {
+        return set1.is_subset(&set2);
+    }
+}
+
+/// Subsets of `universe` ordered by reverse inclusion, for must analyses such
+/// as available expressions and very busy expressions
+#[derive(Debug, Clone)]
+pub struct ReversePowersetLattice<T> {
+    pub universe: HashSet<T>,
+}
+
+impl<T: Eq + Hash + Clone + Debug> Lattice for ReversePowersetLattice<T> {
+    type Element = HashSet<T>;
+
+    fn bottom(&self) -> HashSet<T> {
+        self.universe.clone()
+    }
+
+    fn top(&self) -> HashSet<T> {
+        HashSet::new()
+    }
+
+    fn join(&self, set1: HashSet<T>, set2: HashSet<T>) -> HashSet<T> {
+        return intersection(set1, set2);
+    }
+
+    fn meet(&self, set1: HashSet<T>, set2: HashSet<T>) -> HashSet<T> {
+        return union(set1, set2);
+    }
+
+    fn leq(&self, set1: HashSet<T>, set2: HashSet<T>) -> bool {
+        return set2.is_subset(&set1);
+    }
+}
+
+/// Maps from the variables in `keys` to elements of `value`, ordered pointwise.
+/// Variables missing from an element are read as the bottom of `value`.
+#[derive(Debug, Clone)]
+pub struct MapLattice<L> {
+    pub keys: HashSet<Name>,
+    pub value: L,
+}
+
+impl<L: Lattice> MapLattice<L> {
+    fn get(&self, map: &HashMap<Name, L::Element>, key: &Name) -> L::Element {
+        return map.get(key).cloned().unwrap_or_else(|| self.value.bottom());
+    }
+
+    fn pointwise(
+        &self,
+        map1: HashMap<Name, L::Element>,
+        map2: HashMap<Name, L::Element>,
+        operator: impl Fn(L::Element, L::Element) -> L::Element,
+    ) -> HashMap<Name, L::Element> {
+        return self
+            .keys
+            .iter()
+            .map(|key| {
+                (
+                    key.clone(),
+                    operator(self.get(&map1, key), self.get(&map2, key)),
+                )
+            })
+            .collect();
+    }
+}
+
+impl<L: Lattice> Lattice for MapLattice<L> {
+    type Element = HashMap<Name, L::Element>;
+
+    fn bottom(&self) -> HashMap<Name, L::Element> {
+        self.keys
+            .iter()
+            .map(|key| (key.clone(), self.value.bottom()))
+            .collect()
+    }
+
+    fn top(&self) -> HashMap<Name, L::Element> {
+        self.keys
+            .iter()
+            .map(|key| (key.clone(), self.value.top()))
+            .collect()
+    }
+
+    fn join(
+        &self,
+        map1: HashMap<Name, L::Element>,
+        map2: HashMap<Name, L::Element>,
+    ) -> HashMap<Name, L::Element> {
+        return self.pointwise(map1, map2, |v1, v2| self.value.join(v1, v2));
+    }
+
+    fn meet(
+        &self,
+        map1: HashMap<Name, L::Element>,
+        map2: HashMap<Name, L::Element>,
+    ) -> HashMap<Name, L::Element> {
+        return self.pointwise(map1, map2, |v1, v2| self.value.meet(v1, v2));
+    }
+
+    fn leq(&self, map1: HashMap<Name, L::Element>, map2: HashMap<Name, L::Element>) -> bool {
+        return self
+            .keys
+            .iter()
+            .all(|key| self.value.leq(self.get(&map1, key), self.get(&map2, key)));
+    }
+
+    fn widen(
+        &self,
+        map1: HashMap<Name, L::Element>,
+        map2: HashMap<Name, L::Element>,
+    ) -> HashMap<Name, L::Element> {
+        return self.pointwise(map1, map2, |v1, v2| self.value.widen(v1, v2));
+    }
+
+    fn narrow(
+        &self,
+        map1: HashMap<Name, L::Element>,
+        map2: HashMap<Name, L::Element>,
+    ) -> HashMap<Name, L::Element> {
+        return self.pointwise(map1, map2, |v1, v2| self.value.narrow(v1, v2));
+    }
+}
@@ -0,0 +1,195 @@
+use std::fmt;
+
+use crate::abstract_syntax::Name;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenKind {
+    Ident(Name),
+    Num(u64), // Magnitude of a literal, the parser applies a unary `-`
+
+    /* Keywords */
+    Skip,
+    If,
+    Then,
+    Else,
+    While,
+    Do,
+    True,
+    False,
+    Not,
+    And,
+    Or,
+
+    /* Symbols */
+    Assign,
+    Semicolon,
+    LParen,
+    RParen,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Eq,
+    GT,
+    LT,
+    GEq,
+    LEq,
+
+    Eof,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub start: usize, // Byte offset of the first character
+    pub end: usize,   // Byte offset one past the last character
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LexError {
+    pub message: String,
+    pub position: usize,
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            TokenKind::Ident(name) => write!(f, "identifier `{}`", name),
+            TokenKind::Num(value) => write!(f, "number `{}`", value),
+            TokenKind::Skip => write!(f, "`skip`"),
+            TokenKind::If => write!(f, "`if`"),
+            TokenKind::Then => write!(f, "`then`"),
+            TokenKind::Else => write!(f, "`else`"),
+            TokenKind::While => write!(f, "`while`"),
+            TokenKind::Do => write!(f, "`do`"),
+            TokenKind::True => write!(f, "`true`"),
+            TokenKind::False => write!(f, "`false`"),
+            TokenKind::Not => write!(f, "`not`"),
+            TokenKind::And => write!(f, "`and`"),
+            TokenKind::Or => write!(f, "`or`"),
+            TokenKind::Assign => write!(f, "`:=`"),
+            TokenKind::Semicolon => write!(f, "`;`"),
+            TokenKind::LParen => write!(f, "`(`"),
+            TokenKind::RParen => write!(f, "`)`"),
+            TokenKind::Plus => write!(f, "`+`"),
+            TokenKind::Minus => write!(f, "`-`"),
+            TokenKind::Star => write!(f, "`*`"),
+            TokenKind::Slash => write!(f, "`/`"),
+            TokenKind::Eq => write!(f, "`=`"),
+            TokenKind::GT => write!(f, "`>`"),
+            TokenKind::LT => write!(f, "`<`"),
+            TokenKind::GEq => write!(f, "`>=`"),
+            TokenKind::LEq => write!(f, "`<=`"),
+            TokenKind::Eof => write!(f, "end of input"),
+        };
+    }
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at byte {}", self.message, self.position)
+    }
+}
+
+fn keyword(word: &str) -> Option<TokenKind> {
+    return match word {
+        "skip" => Some(TokenKind::Skip),
+        "if" => Some(TokenKind::If),
+        "then" => Some(TokenKind::Then),
+        "else" => Some(TokenKind::Else),
+        "while" => Some(TokenKind::While),
+        "do" => Some(TokenKind::Do),
+        "true" => Some(TokenKind::True),
+        "false" => Some(TokenKind::False),
+        "not" => Some(TokenKind::Not),
+        "and" => Some(TokenKind::And),
+        "or" => Some(TokenKind::Or),
+        _ => None,
+    };
+}
+
+/// Splits While source text into tokens, always terminated by an Eof token.
+/// Line comments start with `//`.
+pub fn tokenize(source: &str) -> Result<Vec<Token>, LexError> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        let start = i;
+
+        if c.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        if c == b'/' && bytes.get(i + 1) == Some(&b'/') {
+            while i < bytes.len() && bytes[i] != b'\n' {
+                i += 1;
+            }
+            continue;
+        }
+
+        let kind = if c.is_ascii_alphabetic() || c == b'_' {
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                i += 1;
+            }
+            let word = &source[start..i];
+            keyword(word).unwrap_or_else(|| TokenKind::Ident(word.to_string()))
+        } else if c.is_ascii_digit() {
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+            match source[start..i].parse::<u64>() {
+                Ok(value) => TokenKind::Num(value),
+                Err(_) => {
+                    return Err(LexError {
+                        message: format!("integer literal `{}` is too large", &source[start..i]),
+                        position: start,
+                    })
+                }
+            }
+        } else {
+            let next = bytes.get(i + 1).copied();
+            let (kind, len) = match (c, next) {
+                (b':', Some(b'=')) => (TokenKind::Assign, 2),
+                (b'>', Some(b'=')) => (TokenKind::GEq, 2),
+                (b'<', Some(b'=')) => (TokenKind::LEq, 2),
+                (b';', _) => (TokenKind::Semicolon, 1),
+                (b'(', _) => (TokenKind::LParen, 1),
+                (b')', _) => (TokenKind::RParen, 1),
+                (b'+', _) => (TokenKind::Plus, 1),
+                (b'-', _) => (TokenKind::Minus, 1),
+                (b'*', _) => (TokenKind::Star, 1),
+                (b'/', _) => (TokenKind::Slash, 1),
+                (b'=', _) => (TokenKind::Eq, 1),
+                (b'>', _) => (TokenKind::GT, 1),
+                (b'<', _) => (TokenKind::LT, 1),
+                _ => {
+                    let ch = source[start..].chars().next().unwrap_or('?');
+                    return Err(LexError {
+                        message: format!("unexpected character `{}`", ch),
+                        position: start,
+                    });
+                }
+            };
+            i += len;
+            kind
+        };
+
+        tokens.push(Token {
+            kind,
+            start,
+            end: i,
+        });
+    }
+
+    tokens.push(Token {
+        kind: TokenKind::Eof,
+        start: bytes.len(),
+        end: bytes.len(),
+    });
+
+    return Ok(tokens);
+}
@@ -0,0 +1,90 @@
+//! Intraprocedural data flow analysis of the While language of Nielson,
+//! Nielson & Hankin, *Principles of Program Analysis*.
+//!
+//! Programs are parsed from source with [`parse`] into the labelled abstract
+//! syntax of [`abstract_syntax`]. The functions of [`utils`] compute the
+//! textbook notions over it (`blocks`, `init`, `final`, `flow`, ...) and every
+//! analysis is an instance of the monotone [`Framework`] over a [`Lattice`],
+//! solved by the worklist algorithm of [`solve`].
+//!
+//! ```
+//! use while_lang_data_flow_analysis::{lv::LiveVariables, parse, solve};
+//!
+//! let program = parse("x := 2; y := 4; x := 1; if y > x then z := y else z := y * y; x := z")
+//!     .unwrap();
+//! let result = solve(Box::new(LiveVariables { program }));
+//!
+//! assert!(result.exit[&1].is_empty());
+//! assert!(result.entry[&7].contains("z"));
+//! ```
+
+#![allow(clippy::needless_return, clippy::boxed_local)]
+
+/// Labelled abstract syntax of While programs and source spans
+pub mod abstract_syntax;
+/// Available expressions analysis
+pub mod ae;
+/// Control flow graphs with adjacency lists
+pub mod cfg;
+/// Constant propagation over the flat lattice of integers
+pub mod cp;
+/// Error and warning messages pointing into the program source
+pub mod diagnostics;
+/// Non-relational value domains and the analysis shared by them
+pub mod domain;
+/// Graphviz export of control flow graphs and analysis results
+pub mod dot;
+/// Monotone frameworks and their worklist solver
+pub mod framework;
+/// Random well-labelled programs for property based testing
+pub mod generator;
+/// Small-step interpreter producing concrete execution traces
+pub mod interpreter;
+/// Interval analysis with widening and narrowing
+pub mod interval;
+/// JSON exchange format for programs and analysis results
+pub mod json;
+/// Assignment and validation of block labels
+pub mod labelling;
+/// Complete lattices of analysis properties
+pub mod lattice;
+/// Tokens of While source text
+pub mod lexer;
+/// Live variables analysis
+pub mod lv;
+/// Meet over all paths solver for comparison with the MFP solution
+pub mod mop;
+/// Parity analysis
+pub mod parity;
+/// Recursive descent parser for While source text
+pub mod parser;
+/// Textbook notation for programs, blocks and expressions
+pub mod pretty;
+/// Reaching definitions analysis
+pub mod rd;
+/// Plain text rendering of analysis results
+pub mod report;
+/// Sign analysis
+pub mod sign;
+/// Checks of analysis results against concrete executions
+pub mod soundness;
+/// Counters collected while solving an analysis
+pub mod stats;
+/// Step by step tables of the worklist iteration
+pub mod trace;
+/// Blocks, labels, free variables and flow of statements
+pub mod utils;
+/// Very busy expressions analysis
+pub mod vb;
+/// Checks that analysis results solve the data flow equations
+pub mod verify;
+/// Iteration strategies of the worklist solver
+pub mod worklist;
+
+pub use crate::{
+    abstract_syntax::{Block, Label, Program, Statement},
+    framework::{solve, solve_traced, solve_with, AnalysisResult, Edge, Framework},
+    lattice::Lattice,
+    parser::parse,
+    worklist::Strategy,
+};
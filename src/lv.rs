@@ -1,16 +1,17 @@
 use std::collections::HashSet;
 
 use crate::{
-    abstract_syntax::{AssignmentStmt, Block, Condition, Label, Name, Program},
-    framework::{Edge, Framework},
-    utils::{flow_r, fv_be, fv_e, r#final, union},
+    abstract_syntax::{AssignmentStmt, Block, Condition, Name, Program},
+    framework::{Framework, GenKill},
+    lattice::PowersetLattice,
+    utils::{fv_be, fv_e, variables},
 };
 
 pub struct LiveVariables {
     pub program: Box<Program>,
 }
 
-impl Framework<Name> for LiveVariables {
+impl Framework<PowersetLattice<Name>> for LiveVariables {
     fn get_program(&self) -> Box<Program> {
         return self.program.clone();
     }
@@ -19,38 +20,29 @@ impl Framework<Name> for LiveVariables {
         return true;
     }
 
-    fn get_f(&self) -> Vec<Edge> {
-        flow_r(self.program.clone())
-    }
-
-    fn get_e(&self) -> Vec<Label> {
-        r#final(self.program.clone())
-    }
-
     fn get_initial_e(&self) -> HashSet<Name> {
         HashSet::new()
     }
 
-    fn get_initial_others(&self) -> HashSet<Name> {
-        HashSet::new()
-    }
-
-    // set1 está contido no set2
-    fn set_compare(&self, set1: HashSet<Name>, set2: HashSet<Name>) -> bool {
-        return set1.is_subset(&set2);
+    fn get_lattice(&self) -> PowersetLattice<Name> {
+        PowersetLattice {
+            universe: variables(self.program.clone()),
+        }
     }
 
-    // Set union function
-    fn set_union(&self, set1: HashSet<Name>, set2: HashSet<Name>) -> HashSet<Name> {
-        return union(set1, set2);
+    fn fl(&self, block: Box<Block>, entry: HashSet<Name>) -> HashSet<Name> {
+        return self.fl_gen_kill(block, entry);
     }
+}
 
+impl GenKill<Name> for LiveVariables {
     fn kill(&self, block: Box<Block>) -> HashSet<Name> {
         return match *block {
             Block::AssignmentStmt(AssignmentStmt {
                 name,
                 exp: _,
                 label: _,
+                span: _,
             }) => HashSet::from([name]),
             _ => HashSet::new(),
         };
@@ -62,8 +54,13 @@ impl Framework<Name> for LiveVariables {
                 name: _,
                 exp,
                 label: _,
+                span: _,
             }) => fv_e(exp),
-            Block::Condition(Condition { exp, label: _ }) => fv_be(exp),
+            Block::Condition(Condition {
+                exp,
+                label: _,
+                span: _,
+            }) => fv_be(exp),
             _ => HashSet::new(),
         };
     }
@@ -1,447 +1,352 @@
-pub mod abstract_syntax;
-pub mod ae;
-pub mod framework;
-pub mod lv;
-pub mod rd;
-pub mod utils;
-pub mod vb;
+#![allow(clippy::needless_return)]
 
-use crate::{
-    abstract_syntax::{
-        AddExp, ArithmeticExpression, AssignmentStmt, Block, BooleanExpression, CTrue, Condition,
-        Expression, GTExp, IfElseStmt, MulExp, NumExp, SequenceStmt, SkipStmt, Statement, SubExp,
-        VarExp, WhileStmt,
-    },
+mod cli;
+
+use std::{fs, io, io::Read, process};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use while_lang_data_flow_analysis::{
+    abstract_syntax::{Program, Span},
     ae::AvailableExpressions,
-    framework::solve,
+    cfg::Cfg,
+    cp::ConstantPropagation,
+    diagnostics::{Diagnostic, Severity},
+    domain::NARROWING_PASSES,
+    dot::{analysis_to_dot, cfg_to_dot},
+    framework::{self, solve_traced, solve_with, Framework},
+    interpreter::{run as run_program, Execution, Outcome, Store},
+    interval::{IntervalAnalysis, IntervalLattice},
+    json::{program_from_json, program_to_json, result_to_value, results_to_json},
+    labelling::validate_labels,
+    lattice::Lattice,
     lv::LiveVariables,
+    mop::solve_mop,
+    parity::{ParityAnalysis, ParityLattice},
+    parser::parse,
     rd::ReachingDefinition,
-    utils::{assignments, blocks, flow, flow_r, fv_st, init, label, r#final},
+    report::{render_result, render_stats},
+    sign::{SignAnalysis, SignLattice},
+    soundness::check_soundness,
+    stats::SolverStats,
+    utils::{blocks, label},
     vb::VeryBusyExpressions,
+    verify::verify_fixpoint,
+    worklist::Strategy,
 };
 
-fn main() {
-    /*
-    1: while(true) {
-        2: x = 3 + y
-        3: skip
-    }
-    4: if(true){
-        5: x = z
+use crate::cli::{parse_command, Analysis, Command, Format, TraceFormat, USAGE};
+
+fn read_source(path: &str) -> String {
+    let result = if path == "-" {
+        let mut source = String::new();
+        io::stdin().read_to_string(&mut source).map(|_| source)
     } else {
-        6: x = k
+        fs::read_to_string(path)
+    };
+
+    return match result {
+        Ok(source) => source,
+        Err(error) => {
+            eprintln!("error: cannot read {}: {}", path, error);
+            process::exit(1);
+        }
+    };
+}
+
+fn parse_program(path: &str, source: &str) -> Box<Program> {
+    let json = path.ends_with(".json");
+    let parsed = if json {
+        program_from_json(source).map_err(|error| format!("error: invalid program: {}", error))
+    } else {
+        parse(source).map_err(|error| error.to_diagnostic().render(source))
+    };
+
+    let program = match parsed {
+        Ok(program) => program,
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(1);
+        }
+    };
+
+    if let Err(errors) = validate_labels(program.clone()) {
+        for error in errors {
+            // Spans of a JSON program point into the source it was exported
+            // from, not into the JSON, so only the label is shown
+            let mut diagnostic = error.to_diagnostic();
+            if json {
+                diagnostic.span = Span::default();
+            }
+            eprintln!("{}", diagnostic.render(source));
+        }
+        process::exit(1);
     }
-     */
-    let stmt = Box::new(Statement::SequenceStmt(SequenceStmt {
-        s1: Box::new(Statement::WhileStmt(WhileStmt {
-            condition: Condition {
-                exp: Box::new(BooleanExpression::CTrue(CTrue {})),
-                label: 1,
-            },
-            stmt: Box::new(Statement::SequenceStmt(SequenceStmt {
-                s1: Box::new(Statement::AssignmentStmt(AssignmentStmt {
-                    name: "x".to_string(),
-                    exp: Box::new(Expression::ArithmeticExpression(Box::new(
-                        ArithmeticExpression::AddExp(AddExp {
-                            left: Box::new(ArithmeticExpression::NumExp(NumExp { value: 3 })),
-                            right: Box::new(ArithmeticExpression::VarExp(VarExp {
-                                name: "y".to_string(),
-                            })),
-                        }),
-                    ))),
-                    label: 2,
-                })),
-                s2: Box::new(Statement::SkipStmt(SkipStmt { label: 3 })),
-            })),
-        })),
-        s2: Box::new(Statement::IfElseStmt(IfElseStmt {
-            condition: Condition {
-                exp: Box::new(BooleanExpression::CTrue(CTrue {})),
-                label: 4,
+
+    return program;
+}
+
+// How every analysis of an `analyze` command is solved and printed
+struct Options {
+    strategy: Strategy,
+    trace: Option<TraceFormat>,
+    mop: Option<usize>,
+    verify: bool,
+    format: Format,
+}
+
+// What `analyze` produces for one analysis. A JSON result is kept apart to
+// be written with the results of the other analyses in one document.
+struct Analyzed {
+    out: String,
+    json: Option<Value>,
+    stats: SolverStats,
+    errors: Vec<Diagnostic>, // Equations the result does not solve, with --verify
+}
+
+// Solves the analysis and renders its result, preceded by the iteration trace
+// when one is requested. `framework` builds a fresh instance of the analysis,
+// as the solvers take ownership of theirs and the verifier needs another one.
+fn run<L: Lattice>(framework: impl Fn() -> Box<dyn Framework<L>>, options: &Options) -> Analyzed
+where
+    L::Element: Serialize + framework::L,
+{
+    let program = framework().get_program();
+    let mut out = String::new();
+
+    let result = match (options.mop, options.trace) {
+        (Some(bound), _) => solve_mop(framework(), bound),
+        (None, Some(trace_format)) => {
+            let (result, trace) = solve_traced(framework(), options.strategy);
+            out.push_str(&match trace_format {
+                TraceFormat::Text => trace.to_text(),
+                TraceFormat::Latex => trace.to_latex(),
+            });
+            result
+        }
+        (None, None) => solve_with(framework(), options.strategy),
+    };
+
+    let mut errors = Vec::new();
+    if options.verify {
+        if let Err(failures) = verify_fixpoint(framework().as_ref(), &result) {
+            errors = failures
+                .iter()
+                .map(|error| error.to_diagnostic(program.clone()))
+                .collect();
+        }
+    }
+
+    let mut json = None;
+    match options.format {
+        Format::Text => out.push_str(&render_result(&result)),
+        Format::Dot => out.push_str(&analysis_to_dot(program, &result)),
+        Format::Json => json = Some(result_to_value(&result)),
+    }
+    return Analyzed {
+        out,
+        json,
+        stats: result.stats,
+        errors,
+    };
+}
+
+fn analyze(program: Box<Program>, analysis: Analysis, options: &Options) -> Analyzed {
+    let p = || program.clone();
+    return match analysis {
+        Analysis::ReachingDefinitions => {
+            run(|| Box::new(ReachingDefinition { program: p() }), options)
+        }
+        Analysis::AvailableExpressions => {
+            run(|| Box::new(AvailableExpressions { program: p() }), options)
+        }
+        Analysis::LiveVariables => run(|| Box::new(LiveVariables { program: p() }), options),
+        Analysis::VeryBusyExpressions => {
+            run(|| Box::new(VeryBusyExpressions { program: p() }), options)
+        }
+        Analysis::ConstantPropagation => {
+            run(|| Box::new(ConstantPropagation { program: p() }), options)
+        }
+        Analysis::Interval => run(
+            || {
+                Box::new(IntervalAnalysis::new(
+                    p(),
+                    IntervalLattice {},
+                    NARROWING_PASSES,
+                ))
             },
-            then_stmt: Box::new(Statement::AssignmentStmt(AssignmentStmt {
-                name: "x".to_string(),
-                exp: Box::new(Expression::ArithmeticExpression(Box::new(
-                    ArithmeticExpression::VarExp(VarExp {
-                        name: "z".to_string(),
-                    }),
-                ))),
-                label: 5,
-            })),
-            else_stmt: Box::new(Statement::AssignmentStmt(AssignmentStmt {
-                name: "x".to_string(),
-                exp: Box::new(Expression::ArithmeticExpression(Box::new(
-                    ArithmeticExpression::VarExp(VarExp {
-                        name: "k".to_string(),
-                    }),
-                ))),
-                label: 6,
-            })),
-        })),
-    }));
+            options,
+        ),
+        Analysis::Sign => run(
+            || Box::new(SignAnalysis::new(p(), SignLattice {}, NARROWING_PASSES)),
+            options,
+        ),
+        Analysis::Parity => run(
+            || Box::new(ParityAnalysis::new(p(), ParityLattice {}, NARROWING_PASSES)),
+            options,
+        ),
+    };
+}
+
+fn render_cfg(program: Box<Program>, reverse: bool, format: Format) -> String {
+    if format == Format::Dot {
+        return cfg_to_dot(program, reverse);
+    }
+
+    let mut cfg = Cfg::new(program);
+    if reverse {
+        cfg = cfg.reverse();
+    }
 
-    let block = Box::new(Block::AssignmentStmt(AssignmentStmt {
-        name: "x".to_string(),
-        exp: Box::new(Expression::ArithmeticExpression(Box::new(
-            ArithmeticExpression::VarExp(VarExp {
-                name: "z".to_string(),
-            }),
-        ))),
-        label: 5,
-    }));
+    let mut out = String::new();
+    out.push_str(&format!("extremal: {:?}\n", cfg.extremal()));
+    out.push_str(&format!("final: {:?}\n", cfg.finals()));
+    for (l1, l2) in cfg.edges() {
+        out.push_str(&format!("{} -> {}\n", l1, l2));
+    }
+    return out;
+}
 
-    let stmt_blocks = blocks(stmt.clone());
-    let stmt_assignments = assignments(stmt.clone());
-    let stmt_fv = fv_st(stmt.clone());
-    let stmt_init = init(stmt.clone());
-    let stmt_final = r#final(stmt.clone());
-    let block_label = label(block.clone());
-    let stmt_flow = flow(stmt.clone());
-    let stmt_flow_r = flow_r(stmt.clone());
+fn render_labels(program: Box<Program>, format: Format) -> String {
+    if format == Format::Json {
+        return program_to_json(&program) + "\n";
+    }
 
-    println!("stmt_blocks: {:?}", stmt_blocks);
-    println!("stmt_assignments: {:?}", stmt_assignments);
-    println!("stmt_fv: {:?}", stmt_fv);
-    println!("stmt_init: {:?}", stmt_init);
-    println!("stmt_final: {:?}", stmt_final);
-    println!("block_label: {:?}", block_label);
-    println!("stmt_flow: {:?}", stmt_flow);
-    println!("stmt_flow_r: {:?}", stmt_flow_r);
+    let mut out = String::new();
+    for block in blocks(program) {
+        out.push_str(&format!("{}: {:#}\n", label(block.clone()), block));
+    }
+    return out;
+}
 
-    /*
-       1: x = a+b
-       2: y = a*b
-       3: while y>a+b
-       4:   a = a+1
-       5:   x = a+b
-    */
-    let available_expressions_program = Box::new(Statement::SequenceStmt(SequenceStmt {
-        s1: Box::new(Statement::AssignmentStmt(AssignmentStmt {
-            name: "x".to_string(),
-            exp: Box::new(Expression::ArithmeticExpression(Box::new(
-                ArithmeticExpression::AddExp(AddExp {
-                    left: Box::new(ArithmeticExpression::VarExp(VarExp {
-                        name: "a".to_string(),
-                    })),
-                    right: Box::new(ArithmeticExpression::VarExp(VarExp {
-                        name: "b".to_string(),
-                    })),
-                }),
-            ))),
-            label: 1,
-        })),
-        s2: Box::new(Statement::SequenceStmt(SequenceStmt {
-            s1: Box::new(Statement::AssignmentStmt(AssignmentStmt {
-                name: "y".to_string(),
-                exp: Box::new(Expression::ArithmeticExpression(Box::new(
-                    ArithmeticExpression::MulExp(MulExp {
-                        left: Box::new(ArithmeticExpression::VarExp(VarExp {
-                            name: "a".to_string(),
-                        })),
-                        right: Box::new(ArithmeticExpression::VarExp(VarExp {
-                            name: "b".to_string(),
-                        })),
-                    }),
-                ))),
-                label: 2,
-            })),
-            s2: Box::new(Statement::WhileStmt(WhileStmt {
-                condition: Condition {
-                    exp: Box::new(BooleanExpression::GTExp(GTExp {
-                        left: Box::new(ArithmeticExpression::VarExp(VarExp {
-                            name: "y".to_string(),
-                        })),
-                        right: Box::new(ArithmeticExpression::AddExp(AddExp {
-                            left: Box::new(ArithmeticExpression::VarExp(VarExp {
-                                name: "a".to_string(),
-                            })),
-                            right: Box::new(ArithmeticExpression::VarExp(VarExp {
-                                name: "b".to_string(),
-                            })),
-                        })),
-                    })),
-                    label: 3,
-                },
-                stmt: Box::new(Statement::SequenceStmt(SequenceStmt {
-                    s1: Box::new(Statement::AssignmentStmt(AssignmentStmt {
-                        name: "a".to_string(),
-                        exp: Box::new(Expression::ArithmeticExpression(Box::new(
-                            ArithmeticExpression::AddExp(AddExp {
-                                left: Box::new(ArithmeticExpression::VarExp(VarExp {
-                                    name: "a".to_string(),
-                                })),
-                                right: Box::new(ArithmeticExpression::NumExp(NumExp { value: 1 })),
-                            }),
-                        ))),
-                        label: 4,
-                    })),
-                    s2: Box::new(Statement::AssignmentStmt(AssignmentStmt {
-                        name: "x".to_string(),
-                        exp: Box::new(Expression::ArithmeticExpression(Box::new(
-                            ArithmeticExpression::AddExp(AddExp {
-                                left: Box::new(ArithmeticExpression::VarExp(VarExp {
-                                    name: "a".to_string(),
-                                })),
-                                right: Box::new(ArithmeticExpression::VarExp(VarExp {
-                                    name: "b".to_string(),
-                                })),
-                            }),
-                        ))),
-                        label: 5,
-                    })),
-                })),
-            })),
-        })),
-    }));
+fn render_execution(execution: &Execution) -> String {
+    let trace: Vec<String> = execution.trace.iter().map(|l| l.to_string()).collect();
+    let mut out = format!("trace: {}\n", trace.join(" "));
 
-    println!("Available Expressions");
-    solve(Box::new(AvailableExpressions {
-        program: available_expressions_program,
-    }));
+    let mut names: Vec<&String> = execution.store.keys().collect();
+    names.sort();
+    for name in names {
+        out.push_str(&format!("{} = {}\n", name, execution.store[name]));
+    }
+    return out;
+}
 
-    /*
-       1: x = 5
-       2: y = 1
-       3: while x>1
-       4:   y = x*y
-       5:   x = x-1
-    */
-    let reaching_definitions_program = Box::new(Statement::SequenceStmt(SequenceStmt {
-        s1: Box::new(Statement::AssignmentStmt(AssignmentStmt {
-            name: "x".to_string(),
-            exp: Box::new(Expression::ArithmeticExpression(Box::new(
-                ArithmeticExpression::NumExp(NumExp { value: 5 }),
-            ))),
-            label: 1,
-        })),
-        s2: Box::new(Statement::SequenceStmt(SequenceStmt {
-            s1: Box::new(Statement::AssignmentStmt(AssignmentStmt {
-                name: "y".to_string(),
-                exp: Box::new(Expression::ArithmeticExpression(Box::new(
-                    ArithmeticExpression::NumExp(NumExp { value: 1 }),
-                ))),
-                label: 2,
-            })),
-            s2: Box::new(Statement::WhileStmt(WhileStmt {
-                condition: Condition {
-                    exp: Box::new(BooleanExpression::GTExp(GTExp {
-                        left: Box::new(ArithmeticExpression::VarExp(VarExp {
-                            name: "x".to_string(),
-                        })),
-                        right: Box::new(ArithmeticExpression::NumExp(NumExp { value: 1 })),
-                    })),
-                    label: 3,
-                },
-                stmt: Box::new(Statement::SequenceStmt(SequenceStmt {
-                    s1: Box::new(Statement::AssignmentStmt(AssignmentStmt {
-                        name: "y".to_string(),
-                        exp: Box::new(Expression::ArithmeticExpression(Box::new(
-                            ArithmeticExpression::MulExp(MulExp {
-                                left: Box::new(ArithmeticExpression::VarExp(VarExp {
-                                    name: "x".to_string(),
-                                })),
-                                right: Box::new(ArithmeticExpression::VarExp(VarExp {
-                                    name: "y".to_string(),
-                                })),
-                            }),
-                        ))),
-                        label: 4,
-                    })),
-                    s2: Box::new(Statement::AssignmentStmt(AssignmentStmt {
-                        name: "x".to_string(),
-                        exp: Box::new(Expression::ArithmeticExpression(Box::new(
-                            ArithmeticExpression::SubExp(SubExp {
-                                left: Box::new(ArithmeticExpression::VarExp(VarExp {
-                                    name: "x".to_string(),
-                                })),
-                                right: Box::new(ArithmeticExpression::NumExp(NumExp { value: 1 })),
-                            }),
-                        ))),
-                        label: 5,
-                    })),
-                })),
-            })),
-        })),
-    }));
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
 
-    println!("Reaching Definition");
-    solve(Box::new(ReachingDefinition {
-        program: reaching_definitions_program,
-    }));
+    let command = match parse_command(&args) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            process::exit(2);
+        }
+    };
 
-    /*
-       1: x = 2
-       2: y = 4
-       3: x = 1
-       4: if y > x
-       5: then z = y
-       6: else z = y * y
-       7: x = z
-    */
-    let live_variables_program = Box::new(Statement::SequenceStmt(SequenceStmt {
-        s1: Box::new(Statement::AssignmentStmt(AssignmentStmt {
-            name: "x".to_string(),
-            exp: Box::new(Expression::ArithmeticExpression(Box::new(
-                ArithmeticExpression::NumExp(NumExp { value: 2 }),
-            ))),
-            label: 1,
-        })),
-        s2: Box::new(Statement::SequenceStmt(SequenceStmt {
-            s1: Box::new(Statement::AssignmentStmt(AssignmentStmt {
-                name: "y".to_string(),
-                exp: Box::new(Expression::ArithmeticExpression(Box::new(
-                    ArithmeticExpression::NumExp(NumExp { value: 4 }),
-                ))),
-                label: 2,
-            })),
-            s2: Box::new(Statement::SequenceStmt(SequenceStmt {
-                s1: Box::new(Statement::AssignmentStmt(AssignmentStmt {
-                    name: "x".to_string(),
-                    exp: Box::new(Expression::ArithmeticExpression(Box::new(
-                        ArithmeticExpression::NumExp(NumExp { value: 1 }),
-                    ))),
-                    label: 3,
-                })),
-                s2: Box::new(Statement::SequenceStmt(SequenceStmt {
-                    s1: Box::new(Statement::IfElseStmt(IfElseStmt {
-                        condition: Condition {
-                            exp: Box::new(BooleanExpression::GTExp(GTExp {
-                                left: Box::new(ArithmeticExpression::VarExp(VarExp {
-                                    name: "y".to_string(),
-                                })),
-                                right: Box::new(ArithmeticExpression::VarExp(VarExp {
-                                    name: "x".to_string(),
-                                })),
-                            })),
-                            label: 4,
-                        },
-                        then_stmt: Box::new(Statement::AssignmentStmt(AssignmentStmt {
-                            name: "z".to_string(),
-                            exp: Box::new(Expression::ArithmeticExpression(Box::new(
-                                ArithmeticExpression::VarExp(VarExp {
-                                    name: "y".to_string(),
-                                }),
-                            ))),
-                            label: 5,
-                        })),
-                        else_stmt: Box::new(Statement::AssignmentStmt(AssignmentStmt {
-                            name: "z".to_string(),
-                            exp: Box::new(Expression::ArithmeticExpression(Box::new(
-                                ArithmeticExpression::MulExp(MulExp {
-                                    left: Box::new(ArithmeticExpression::VarExp(VarExp {
-                                        name: "y".to_string(),
-                                    })),
-                                    right: Box::new(ArithmeticExpression::VarExp(VarExp {
-                                        name: "y".to_string(),
-                                    })),
-                                }),
-                            ))),
-                            label: 6,
-                        })),
-                    })),
-                    s2: Box::new(Statement::AssignmentStmt(AssignmentStmt {
-                        name: "x".to_string(),
-                        exp: Box::new(Expression::ArithmeticExpression(Box::new(
-                            ArithmeticExpression::VarExp(VarExp {
-                                name: "z".to_string(),
-                            }),
-                        ))),
-                        label: 7,
-                    })),
-                })),
-            })),
-        })),
-    }));
+    match command {
+        Command::Analyze {
+            analyses,
+            strategy,
+            stats,
+            trace,
+            mop,
+            verify,
+            format,
+            path,
+        } => {
+            let source = read_source(&path);
+            let program = parse_program(&path, &source);
+            let options = Options {
+                strategy,
+                trace,
+                mop,
+                verify,
+                format,
+            };
+            let mut failed = false;
+            let mut results = Vec::new();
+            for analysis in analyses {
+                if format == Format::Text {
+                    println!("{}", analysis);
+                }
+                let analyzed = analyze(program.clone(), analysis, &options);
+                print!("{}", analyzed.out);
+                if let Some(json) = analyzed.json {
+                    results.push((analysis.name(), json));
+                }
+                if stats {
+                    eprint!("{}\n{}", analysis, render_stats(&analyzed.stats));
+                }
+                for error in analyzed.errors.iter() {
+                    let error = Diagnostic {
+                        message: format!("{}: {}", analysis, error.message),
+                        ..error.clone()
+                    };
+                    eprintln!("{}", error.render(&source));
+                }
+                failed |= !analyzed.errors.is_empty();
+            }
+            if format == Format::Json {
+                println!("{}", results_to_json(results));
+            }
+            if failed {
+                process::exit(1);
+            }
+        }
+        Command::Cfg {
+            reverse,
+            format,
+            path,
+        } => {
+            let program = parse_program(&path, &read_source(&path));
+            print!("{}", render_cfg(program, reverse, format));
+        }
+        Command::Labels { format, path } => {
+            let program = parse_program(&path, &read_source(&path));
+            print!("{}", render_labels(program, format));
+        }
+        Command::Run { steps, store, path } => {
+            let program = parse_program(&path, &read_source(&path));
+            let execution = run_program(program, store.into_iter().collect(), steps);
+            print!("{}", render_execution(&execution));
+            if execution.outcome != Outcome::Terminated {
+                eprintln!("error: {}", execution.outcome);
+                process::exit(1);
+            }
+        }
+        Command::Check { steps, store, path } => {
+            let source = read_source(&path);
+            let program = parse_program(&path, &source);
+            let store: Store = store.into_iter().collect();
 
-    println!("Live Variables");
-    solve(Box::new(LiveVariables {
-        program: live_variables_program,
-    }));
+            let violations = check_soundness(program.clone(), store.clone(), steps);
+            for violation in &violations {
+                println!(
+                    "{}",
+                    violation.to_diagnostic(program.clone()).render(&source)
+                );
+            }
 
-    /*
-       1: if a > b  then
-       2:   x = b - a
-       3:   y = a - b
-          else
-       4:   y = b - a
-       5:   x = a - b
-    */
-    let very_busy_expressions_program = Box::new(Statement::IfElseStmt(IfElseStmt {
-        condition: Condition {
-            exp: Box::new(BooleanExpression::GTExp(GTExp {
-                left: Box::new(ArithmeticExpression::VarExp(VarExp {
-                    name: "a".to_string(),
-                })),
-                right: Box::new(ArithmeticExpression::VarExp(VarExp {
-                    name: "b".to_string(),
-                })),
-            })),
-            label: 1,
-        },
-        then_stmt: Box::new(Statement::SequenceStmt(SequenceStmt {
-            s1: Box::new(Statement::AssignmentStmt(AssignmentStmt {
-                name: "x".to_string(),
-                exp: Box::new(Expression::ArithmeticExpression(Box::new(
-                    ArithmeticExpression::SubExp(SubExp {
-                        left: Box::new(ArithmeticExpression::VarExp(VarExp {
-                            name: "b".to_string(),
-                        })),
-                        right: Box::new(ArithmeticExpression::VarExp(VarExp {
-                            name: "a".to_string(),
-                        })),
-                    }),
-                ))),
-                label: 2,
-            })),
-            s2: Box::new(Statement::AssignmentStmt(AssignmentStmt {
-                name: "y".to_string(),
-                exp: Box::new(Expression::ArithmeticExpression(Box::new(
-                    ArithmeticExpression::SubExp(SubExp {
-                        left: Box::new(ArithmeticExpression::VarExp(VarExp {
-                            name: "a".to_string(),
-                        })),
-                        right: Box::new(ArithmeticExpression::VarExp(VarExp {
-                            name: "b".to_string(),
-                        })),
-                    }),
-                ))),
-                label: 3,
-            })),
-        })),
-        else_stmt: Box::new(Statement::SequenceStmt(SequenceStmt {
-            s1: Box::new(Statement::AssignmentStmt(AssignmentStmt {
-                name: "y".to_string(),
-                exp: Box::new(Expression::ArithmeticExpression(Box::new(
-                    ArithmeticExpression::SubExp(SubExp {
-                        left: Box::new(ArithmeticExpression::VarExp(VarExp {
-                            name: "b".to_string(),
-                        })),
-                        right: Box::new(ArithmeticExpression::VarExp(VarExp {
-                            name: "a".to_string(),
-                        })),
-                    }),
-                ))),
-                label: 4,
-            })),
-            s2: Box::new(Statement::AssignmentStmt(AssignmentStmt {
-                name: "x".to_string(),
-                exp: Box::new(Expression::ArithmeticExpression(Box::new(
-                    ArithmeticExpression::SubExp(SubExp {
-                        left: Box::new(ArithmeticExpression::VarExp(VarExp {
-                            name: "a".to_string(),
-                        })),
-                        right: Box::new(ArithmeticExpression::VarExp(VarExp {
-                            name: "b".to_string(),
-                        })),
-                    }),
-                ))),
-                label: 5,
-            })),
-        })),
-    }));
+            // Blocks after the point a run stopped at are not checked
+            let execution = run_program(program.clone(), store, steps);
+            if let (Some(l), false) = (
+                execution.trace.last(),
+                execution.outcome == Outcome::Terminated,
+            ) {
+                let note = Diagnostic::at_label(
+                    Severity::Note,
+                    format!("the run stopped here: {}", execution.outcome),
+                    program,
+                    *l,
+                );
+                println!("{}", note.render(&source));
+            }
 
-    println!("Very busy expressions");
-    solve(Box::new(VeryBusyExpressions {
-        program: very_busy_expressions_program,
-    }));
+            if !violations.is_empty() {
+                process::exit(1);
+            }
+            println!("no violations");
+        }
+        Command::Help => println!("{}", USAGE),
+    }
 }
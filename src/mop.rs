@@ -0,0 +1,125 @@
+use std::{collections::HashMap, time::Instant};
+
+use crate::{
+    abstract_syntax::Label,
+    framework::{AnalysisResult, Framework},
+    lattice::Lattice,
+    stats::SolverStats,
+};
+
+/*
+   Meet over all paths solution: the transfer functions are composed along
+   every path from an extremal label and the results are combined per label,
+   instead of combining at every join point as `solve` does. The two agree for
+   distributive analyses (the bit vector ones); for analyses such as constant
+   propagation MOP can be strictly more precise.
+
+   Only paths of at most `bound` edges are enumerated, so the solution
+   under-approximates MOP unless the bound covers the longest path, which a
+   program with loops does not have.
+*/
+
+/// Joins the transfer functions along every path of at most `bound` edges.
+/// The number of paths is exponential in the bound: a sequence of n
+/// conditionals alone has 2^n paths through it, so keep the bound small.
+pub fn solve_mop<L: Lattice>(
+    framework: Box<dyn Framework<L>>,
+    bound: usize,
+) -> AnalysisResult<L::Element> {
+    let start = Instant::now();
+    let mut stats = SolverStats::default();
+
+    let lattice = framework.get_lattice();
+    let cfg = framework.get_cfg();
+
+    // Values reaching every label and leaving it, joined over all paths
+    let mut reaching: HashMap<Label, L::Element> = HashMap::new();
+    let mut leaving: HashMap<Label, L::Element> = HashMap::new();
+    for l in cfg.labels() {
+        reaching.insert(*l, lattice.bottom());
+        leaving.insert(*l, lattice.bottom());
+    }
+
+    // Depth-first over the paths, each entry is the last label of a path,
+    // the value reaching it along the path and the number of edges so far
+    let mut paths: Vec<(Label, L::Element, usize)> = cfg
+        .extremal()
+        .iter()
+        .map(|l| (*l, framework.get_initial_e(), 0))
+        .collect();
+
+    while let Some((l, value, length)) = paths.pop() {
+        stats.record_pop(l);
+
+        let joined = lattice.join(reaching[&l].clone(), value.clone());
+        if joined != reaching[&l] {
+            stats.record_update(l);
+            reaching.insert(l, joined);
+        }
+
+        stats.record_transfer(l);
+        let transferred = framework.fl(cfg.block(l), value.clone());
+        let joined = lattice.join(leaving[&l].clone(), transferred);
+        leaving.insert(l, joined);
+
+        if length < bound {
+            for next in cfg.successors(l) {
+                stats.record_transfer(l);
+                let exit = framework.fl_edge((l, *next), cfg.block(l), value.clone());
+                paths.push((*next, exit, length + 1));
+            }
+        }
+    }
+
+    stats.time = start.elapsed();
+    if !cfg.is_reversed() {
+        return AnalysisResult {
+            entry: reaching,
+            exit: leaving,
+            stats,
+        };
+    }
+    return AnalysisResult {
+        entry: leaving,
+        exit: reaching,
+        stats,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cp::{Constant, ConstantPropagation},
+        framework::solve,
+        parser::parse,
+    };
+
+    #[test]
+    fn mop_is_more_precise_than_mfp_for_constant_propagation() {
+        let program = parse(include_str!("../programs/branches.while")).unwrap();
+        let mfp = solve(Box::new(ConstantPropagation {
+            program: program.clone(),
+        }));
+        // The longest path has 3 edges
+        let mop = solve_mop(Box::new(ConstantPropagation { program }), 10);
+
+        assert_eq!(mfp.exit[&6]["c"], Constant::Top);
+        assert_eq!(mop.exit[&6]["c"], Constant::Value(5));
+        assert_ne!(mfp.exit[&6], mop.exit[&6]);
+    }
+
+    #[test]
+    fn bound_stops_the_enumeration() {
+        // 2^20 paths run through the whole program
+        let source = vec!["if x > 0 then y := 1 else y := 2"; 20].join("; ");
+        let program = parse(&source).unwrap();
+        let mop = solve_mop(Box::new(ConstantPropagation { program }), 4);
+
+        // The four paths of 4 edges end at the third conditional, label 7,
+        // and none goes further
+        assert_eq!(mop.stats.total_pops(), 1 + 2 + 2 + 4 + 4);
+        assert_ne!(mop.entry[&7]["y"], Constant::Bottom);
+        assert_eq!(mop.entry[&8]["y"], Constant::Bottom);
+    }
+}
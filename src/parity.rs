@@ -0,0 +1,198 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    abstract_syntax::Integer,
+    domain::{Relation, ValueAnalysis, ValueDomain},
+    framework::L,
+    lattice::Lattice,
+};
+
+/// Flat lattice of parities: Bottom < Even, Odd < Top
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Parity {
+    Bottom,
+    Even,
+    Odd,
+    Top,
+}
+
+impl Parity {
+    pub fn of(value: Integer) -> Parity {
+        if value % 2 == 0 {
+            return Parity::Even;
+        }
+        return Parity::Odd;
+    }
+}
+
+impl L for Parity {
+    fn key(&self) -> String {
+        return String::from(match self {
+            Parity::Bottom => "⊥",
+            Parity::Even => "even",
+            Parity::Odd => "odd",
+            Parity::Top => "⊤",
+        });
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParityLattice {}
+
+impl Lattice for ParityLattice {
+    type Element = Parity;
+
+    fn bottom(&self) -> Parity {
+        Parity::Bottom
+    }
+
+    fn top(&self) -> Parity {
+        Parity::Top
+    }
+
+    fn join(&self, p1: Parity, p2: Parity) -> Parity {
+        return match (p1, p2) {
+            (Parity::Bottom, p) | (p, Parity::Bottom) => p,
+            (p1, p2) if p1 == p2 => p1,
+            _ => Parity::Top,
+        };
+    }
+
+    fn meet(&self, p1: Parity, p2: Parity) -> Parity {
+        return match (p1, p2) {
+            (Parity::Top, p) | (p, Parity::Top) => p,
+            (p1, p2) if p1 == p2 => p1,
+            _ => Parity::Bottom,
+        };
+    }
+
+    fn leq(&self, p1: Parity, p2: Parity) -> bool {
+        return p1 == Parity::Bottom || p2 == Parity::Top || p1 == p2;
+    }
+}
+
+impl ValueDomain for ParityLattice {
+    fn constant(&self, value: Integer) -> Parity {
+        return Parity::of(value);
+    }
+
+    fn add(&self, p1: Parity, p2: Parity) -> Parity {
+        return match (p1, p2) {
+            (Parity::Bottom, _) | (_, Parity::Bottom) => Parity::Bottom,
+            (Parity::Top, _) | (_, Parity::Top) => Parity::Top,
+            (p1, p2) if p1 == p2 => Parity::Even,
+            _ => Parity::Odd,
+        };
+    }
+
+    /// Subtraction preserves parity exactly like addition
+    fn sub(&self, p1: Parity, p2: Parity) -> Parity {
+        return self.add(p1, p2);
+    }
+
+    fn mul(&self, p1: Parity, p2: Parity) -> Parity {
+        return match (p1, p2) {
+            (Parity::Bottom, _) | (_, Parity::Bottom) => Parity::Bottom,
+            (Parity::Even, _) | (_, Parity::Even) => Parity::Even,
+            (Parity::Odd, Parity::Odd) => Parity::Odd,
+            _ => Parity::Top,
+        };
+    }
+
+    /// The parity of a quotient does not follow from the parities of its operands
+    fn div(&self, p1: Parity, p2: Parity) -> Parity {
+        return match (p1, p2) {
+            (Parity::Bottom, _) | (_, Parity::Bottom) => Parity::Bottom,
+            _ => Parity::Top,
+        };
+    }
+
+    fn restrict(&self, relation: Relation, other: Parity) -> Parity {
+        return match relation {
+            Relation::Eq => other,
+            _ => Parity::Top,
+        };
+    }
+
+    fn satisfiable(&self, relation: Relation, left: Parity, right: Parity) -> bool {
+        return match relation {
+            Relation::Eq => self.meet(left, right) != Parity::Bottom,
+            _ => left != Parity::Bottom && right != Parity::Bottom,
+        };
+    }
+}
+
+pub type ParityAnalysis = ValueAnalysis<ParityLattice>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use Parity::{Bottom, Even, Odd, Top};
+
+    // Expected results for every pair of parities, rows and columns in the
+    // order ⊥, even, odd, ⊤
+    fn assert_table(operator: impl Fn(Parity, Parity) -> Parity, table: [[Parity; 4]; 4]) {
+        let parities = [Bottom, Even, Odd, Top];
+        for (i, p1) in parities.iter().enumerate() {
+            for (j, p2) in parities.iter().enumerate() {
+                assert_eq!(operator(*p1, *p2), table[i][j], "{:?} {:?}", p1, p2);
+            }
+        }
+    }
+
+    #[test]
+    fn addition_and_subtraction() {
+        let lattice = ParityLattice {};
+        let table = [
+            [Bottom, Bottom, Bottom, Bottom],
+            [Bottom, Even, Odd, Top],
+            [Bottom, Odd, Even, Top],
+            [Bottom, Top, Top, Top],
+        ];
+        assert_table(|p1, p2| lattice.add(p1, p2), table);
+        assert_table(|p1, p2| lattice.sub(p1, p2), table);
+    }
+
+    #[test]
+    fn multiplication() {
+        let lattice = ParityLattice {};
+        assert_table(
+            |p1, p2| lattice.mul(p1, p2),
+            [
+                [Bottom, Bottom, Bottom, Bottom],
+                [Bottom, Even, Even, Even],
+                [Bottom, Even, Odd, Top],
+                [Bottom, Even, Top, Top],
+            ],
+        );
+    }
+
+    #[test]
+    fn division() {
+        let lattice = ParityLattice {};
+        assert_table(
+            |p1, p2| lattice.div(p1, p2),
+            [
+                [Bottom, Bottom, Bottom, Bottom],
+                [Bottom, Top, Top, Top],
+                [Bottom, Top, Top, Top],
+                [Bottom, Top, Top, Top],
+            ],
+        );
+    }
+
+    #[test]
+    fn wrapping_preserves_parity() {
+        let lattice = ParityLattice {};
+        let max = Integer::MAX;
+        assert_eq!(
+            lattice.add(Parity::of(max), Parity::of(1)),
+            Parity::of(max.wrapping_add(1))
+        );
+        assert_eq!(
+            lattice.mul(Parity::of(max), Parity::of(3)),
+            Parity::of(max.wrapping_mul(3))
+        );
+    }
+}
@@ -0,0 +1,539 @@
+use std::fmt;
+
+use crate::{
+    abstract_syntax::{
+        AddExp, AndExp, ArithmeticExpression, AssignmentStmt, BooleanExpression, CFalse, CTrue,
+        Condition, DivExp, EqExp, Expression, GEqExp, GTExp, IfElseStmt, Integer, LEqExp, LTExp,
+        MulExp, NotExp, NumExp, OrExp, Program, SequenceStmt, SkipStmt, Span, Statement, SubExp,
+        VarExp, WhileStmt, UNDEF,
+    },
+    diagnostics::Diagnostic,
+    labelling::relabel,
+    lexer::{tokenize, Token, TokenKind},
+};
+
+/*
+   Concrete syntax accepted by the parser:
+
+   S ::= x := a | x := b | skip | S1; S2 | if b then S1 else S2 | while b do S | (S)
+   a ::= x | n | -n | a1 + a2 | a1 - a2 | a1 * a2 | a1 / a2 | (a)
+   b ::= true | false | not b | b1 and b2 | b1 or b2 | a1 op a2 | (b)   op in = > < >= <=
+
+   `;` binds loosest, so the body of a `while` or a branch of an `if` holding
+   more than one statement must be parenthesised. Blocks are labelled 1, 2, ...
+   in the order they appear in the source by `labelling::relabel`.
+*/
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl ParseError {
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        return Diagnostic::error(self.message.clone(), self.span);
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {}",
+            self.message, self.span.line, self.span.column
+        )
+    }
+}
+
+pub fn parse(source: &str) -> Result<Box<Program>, ParseError> {
+    let tokens = tokenize(source).map_err(|error| ParseError {
+        message: error.message,
+        span: Span::from_offsets(source, error.position, error.position + 1),
+    })?;
+
+    let mut parser = Parser {
+        source,
+        tokens,
+        position: 0,
+    };
+
+    let program = parser.statement()?;
+    parser.expect(TokenKind::Eof)?;
+
+    return Ok(relabel(program));
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> &TokenKind {
+        return &self.tokens[self.position].kind;
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.position].clone();
+        if token.kind != TokenKind::Eof {
+            self.position += 1;
+        }
+        return token;
+    }
+
+    fn eat(&mut self, kind: TokenKind) -> bool {
+        if *self.peek() == kind {
+            self.advance();
+            return true;
+        }
+        return false;
+    }
+
+    fn expect(&mut self, kind: TokenKind) -> Result<Token, ParseError> {
+        if *self.peek() == kind {
+            return Ok(self.advance());
+        }
+        return Err(self.error(format!("expected {}, found {}", kind, self.peek())));
+    }
+
+    fn error(&self, message: String) -> ParseError {
+        let token = &self.tokens[self.position];
+
+        // End of input has no text to point at, the end of the last token
+        // does and stays on a line the source actually has
+        if token.kind == TokenKind::Eof && self.position > 0 {
+            let end = self.tokens[self.position - 1].end;
+            return ParseError {
+                message,
+                span: self.span(end, end),
+            };
+        }
+
+        ParseError {
+            message,
+            span: self.span(token.start, token.end.max(token.start + 1)),
+        }
+    }
+
+    fn start(&self) -> usize {
+        return self.tokens[self.position].start;
+    }
+
+    /// Span from `start` to the end of the last consumed token
+    fn span_from(&self, start: usize) -> Span {
+        let end = if self.position == 0 {
+            start
+        } else {
+            self.tokens[self.position - 1].end
+        };
+        return self.span(start, end);
+    }
+
+    fn span(&self, start: usize, end: usize) -> Span {
+        return Span::from_offsets(self.source, start, end.min(self.source.len()));
+    }
+
+    /* Statements */
+
+    fn statement(&mut self) -> Result<Box<Statement>, ParseError> {
+        let s1 = self.simple_statement()?;
+
+        if self.eat(TokenKind::Semicolon) {
+            let s2 = self.statement()?;
+            return Ok(Box::new(Statement::SequenceStmt(SequenceStmt { s1, s2 })));
+        }
+
+        return Ok(s1);
+    }
+
+    fn simple_statement(&mut self) -> Result<Box<Statement>, ParseError> {
+        return match self.peek().clone() {
+            TokenKind::Ident(name) => {
+                let start = self.start();
+                self.advance();
+                self.expect(TokenKind::Assign)?;
+                let exp = self.expression()?;
+                Ok(Box::new(Statement::AssignmentStmt(AssignmentStmt {
+                    name,
+                    exp,
+                    label: UNDEF,
+                    span: self.span_from(start),
+                })))
+            }
+            TokenKind::Skip => {
+                let start = self.start();
+                self.advance();
+                Ok(Box::new(Statement::SkipStmt(SkipStmt {
+                    label: UNDEF,
+                    span: self.span_from(start),
+                })))
+            }
+            TokenKind::If => {
+                self.advance();
+                let condition = self.condition()?;
+                self.expect(TokenKind::Then)?;
+                let then_stmt = self.simple_statement()?;
+                self.expect(TokenKind::Else)?;
+                let else_stmt = self.simple_statement()?;
+                Ok(Box::new(Statement::IfElseStmt(IfElseStmt {
+                    condition,
+                    then_stmt,
+                    else_stmt,
+                })))
+            }
+            TokenKind::While => {
+                self.advance();
+                let condition = self.condition()?;
+                self.expect(TokenKind::Do)?;
+                let stmt = self.simple_statement()?;
+                Ok(Box::new(Statement::WhileStmt(WhileStmt {
+                    condition,
+                    stmt,
+                })))
+            }
+            TokenKind::LParen => {
+                self.advance();
+                let stmt = self.statement()?;
+                self.expect(TokenKind::RParen)?;
+                Ok(stmt)
+            }
+            other => Err(self.error(format!("expected a statement, found {}", other))),
+        };
+    }
+
+    fn condition(&mut self) -> Result<Condition, ParseError> {
+        let start = self.start();
+        let exp = self.boolean_expression()?;
+        return Ok(Condition {
+            exp,
+            label: UNDEF,
+            span: self.span_from(start),
+        });
+    }
+
+    /// The right hand side of an assignment is arithmetic unless it cannot be
+    /// read as a complete arithmetic expression.
+    fn expression(&mut self) -> Result<Box<Expression>, ParseError> {
+        let start = self.position;
+
+        if let Ok(exp) = self.arithmetic_expression() {
+            if matches!(
+                self.peek(),
+                TokenKind::Semicolon | TokenKind::RParen | TokenKind::Else | TokenKind::Eof
+            ) {
+                return Ok(Box::new(Expression::ArithmeticExpression(exp)));
+            }
+        }
+
+        self.position = start;
+        let exp = self.boolean_expression()?;
+        return Ok(Box::new(Expression::BooleanExpression(exp)));
+    }
+
+    /* Boolean expressions */
+
+    fn boolean_expression(&mut self) -> Result<Box<BooleanExpression>, ParseError> {
+        let mut left = self.boolean_term()?;
+
+        while self.eat(TokenKind::Or) {
+            let right = self.boolean_term()?;
+            left = Box::new(BooleanExpression::OrExp(OrExp { left, right }));
+        }
+
+        return Ok(left);
+    }
+
+    fn boolean_term(&mut self) -> Result<Box<BooleanExpression>, ParseError> {
+        let mut left = self.boolean_factor()?;
+
+        while self.eat(TokenKind::And) {
+            let right = self.boolean_factor()?;
+            left = Box::new(BooleanExpression::AndExp(AndExp { left, right }));
+        }
+
+        return Ok(left);
+    }
+
+    fn boolean_factor(&mut self) -> Result<Box<BooleanExpression>, ParseError> {
+        return match self.peek() {
+            TokenKind::True => {
+                self.advance();
+                Ok(Box::new(BooleanExpression::CTrue(CTrue {})))
+            }
+            TokenKind::False => {
+                self.advance();
+                Ok(Box::new(BooleanExpression::CFalse(CFalse {})))
+            }
+            TokenKind::Not => {
+                self.advance();
+                let exp = self.boolean_factor()?;
+                Ok(Box::new(BooleanExpression::NotExp(NotExp { exp })))
+            }
+            TokenKind::LParen => {
+                // `(` opens either a parenthesised boolean expression or the
+                // left operand of a comparison such as `(a + b) > c`
+                let start = self.position;
+                if let Ok(exp) = self.relation() {
+                    return Ok(exp);
+                }
+
+                self.position = start;
+                self.advance();
+                let exp = self.boolean_expression()?;
+                self.expect(TokenKind::RParen)?;
+                Ok(exp)
+            }
+            _ => self.relation(),
+        };
+    }
+
+    fn relation(&mut self) -> Result<Box<BooleanExpression>, ParseError> {
+        let left = self.arithmetic_expression()?;
+
+        let operator = self.peek().clone();
+        match operator {
+            TokenKind::Eq | TokenKind::GT | TokenKind::LT | TokenKind::GEq | TokenKind::LEq => {
+                self.advance();
+            }
+            other => {
+                return Err(self.error(format!("expected a comparison operator, found {}", other)))
+            }
+        }
+
+        let right = self.arithmetic_expression()?;
+
+        return Ok(Box::new(match operator {
+            TokenKind::Eq => BooleanExpression::EqExp(EqExp { left, right }),
+            TokenKind::GT => BooleanExpression::GTExp(GTExp { left, right }),
+            TokenKind::LT => BooleanExpression::LTExp(LTExp { left, right }),
+            TokenKind::GEq => BooleanExpression::GEqExp(GEqExp { left, right }),
+            _ => BooleanExpression::LEqExp(LEqExp { left, right }),
+        }));
+    }
+
+    /* Arithmetic expressions */
+
+    fn arithmetic_expression(&mut self) -> Result<Box<ArithmeticExpression>, ParseError> {
+        let mut left = self.arithmetic_term()?;
+
+        loop {
+            if self.eat(TokenKind::Plus) {
+                let right = self.arithmetic_term()?;
+                left = Box::new(ArithmeticExpression::AddExp(AddExp { left, right }));
+            } else if self.eat(TokenKind::Minus) {
+                let right = self.arithmetic_term()?;
+                left = Box::new(ArithmeticExpression::SubExp(SubExp { left, right }));
+            } else {
+                return Ok(left);
+            }
+        }
+    }
+
+    fn arithmetic_term(&mut self) -> Result<Box<ArithmeticExpression>, ParseError> {
+        let mut left = self.arithmetic_factor()?;
+
+        loop {
+            if self.eat(TokenKind::Star) {
+                let right = self.arithmetic_factor()?;
+                left = Box::new(ArithmeticExpression::MulExp(MulExp { left, right }));
+            } else if self.eat(TokenKind::Slash) {
+                let right = self.arithmetic_factor()?;
+                left = Box::new(ArithmeticExpression::DivExp(DivExp { left, right }));
+            } else {
+                return Ok(left);
+            }
+        }
+    }
+
+    // Value of the literal at the current token. Integer has one more negative
+    // value than positive ones, so the sign is applied before the range check.
+    fn integer(&self, magnitude: u64, negative: bool) -> Result<Integer, ParseError> {
+        let value = if negative {
+            -(magnitude as i128)
+        } else {
+            magnitude as i128
+        };
+        return Integer::try_from(value)
+            .map_err(|_| self.error(format!("integer literal `{}` is too large", value)));
+    }
+
+    fn arithmetic_factor(&mut self) -> Result<Box<ArithmeticExpression>, ParseError> {
+        return match self.peek().clone() {
+            TokenKind::Ident(name) => {
+                self.advance();
+                Ok(Box::new(ArithmeticExpression::VarExp(VarExp { name })))
+            }
+            TokenKind::Num(magnitude) => {
+                let value = self.integer(magnitude, false)?;
+                self.advance();
+                Ok(Box::new(ArithmeticExpression::NumExp(NumExp { value })))
+            }
+            TokenKind::Minus => {
+                self.advance();
+                match self.peek().clone() {
+                    TokenKind::Num(magnitude) => {
+                        let value = self.integer(magnitude, true)?;
+                        self.advance();
+                        Ok(Box::new(ArithmeticExpression::NumExp(NumExp { value })))
+                    }
+                    other => Err(self.error(format!(
+                        "expected a number after unary `-`, found {}",
+                        other
+                    ))),
+                }
+            }
+            TokenKind::LParen => {
+                self.advance();
+                let exp = self.arithmetic_expression()?;
+                self.expect(TokenKind::RParen)?;
+                Ok(exp)
+            }
+            other => Err(self.error(format!(
+                "expected an arithmetic expression, found {}",
+                other
+            ))),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Right hand side of the first assignment of `source`
+    fn right_hand_side(source: &str) -> Expression {
+        let mut stmt = parse(source).unwrap();
+        while let Statement::SequenceStmt(SequenceStmt { s1, s2: _ }) = *stmt {
+            stmt = s1;
+        }
+        return match *stmt {
+            Statement::AssignmentStmt(AssignmentStmt { exp, .. }) => *exp,
+            other => panic!("not an assignment: {:?}", other),
+        };
+    }
+
+    fn arithmetic(source: &str) -> ArithmeticExpression {
+        return match right_hand_side(source) {
+            Expression::ArithmeticExpression(exp) => *exp,
+            other => panic!("not arithmetic: {:?}", other),
+        };
+    }
+
+    fn var(name: &str) -> Box<ArithmeticExpression> {
+        return Box::new(ArithmeticExpression::VarExp(VarExp {
+            name: String::from(name),
+        }));
+    }
+
+    fn num(value: i64) -> Box<ArithmeticExpression> {
+        return Box::new(ArithmeticExpression::NumExp(NumExp { value }));
+    }
+
+    fn sub(
+        left: Box<ArithmeticExpression>,
+        right: Box<ArithmeticExpression>,
+    ) -> Box<ArithmeticExpression> {
+        return Box::new(ArithmeticExpression::SubExp(SubExp { left, right }));
+    }
+
+    fn div(
+        left: Box<ArithmeticExpression>,
+        right: Box<ArithmeticExpression>,
+    ) -> Box<ArithmeticExpression> {
+        return Box::new(ArithmeticExpression::DivExp(DivExp { left, right }));
+    }
+
+    #[test]
+    fn multiplication_binds_tighter_than_addition() {
+        let expected = ArithmeticExpression::AddExp(AddExp {
+            left: var("a"),
+            right: Box::new(ArithmeticExpression::MulExp(MulExp {
+                left: var("b"),
+                right: var("c"),
+            })),
+        });
+        assert_eq!(arithmetic("x := a + b * c"), expected);
+        assert_eq!(arithmetic("x := a + (b * c)"), expected);
+    }
+
+    #[test]
+    fn subtraction_and_division_associate_to_the_left() {
+        assert_eq!(
+            arithmetic("x := a - b - c"),
+            *sub(sub(var("a"), var("b")), var("c"))
+        );
+        assert_eq!(
+            arithmetic("x := a - (b - c)"),
+            *sub(var("a"), sub(var("b"), var("c")))
+        );
+        assert_eq!(
+            arithmetic("x := a / b / c"),
+            *div(div(var("a"), var("b")), var("c"))
+        );
+        assert_eq!(
+            arithmetic("x := a / (b / c)"),
+            *div(var("a"), div(var("b"), var("c")))
+        );
+    }
+
+    #[test]
+    fn negative_literals() {
+        assert_eq!(arithmetic("x := -5"), *num(-5));
+        assert_eq!(arithmetic("x := 3 - -2"), *sub(num(3), num(-2)));
+
+        let error = parse("x := -y").unwrap_err();
+        assert!(error
+            .message
+            .starts_with("expected a number after unary `-`"));
+    }
+
+    #[test]
+    fn literals_cover_the_range_of_integer() {
+        assert_eq!(arithmetic("x := 9223372036854775807"), *num(i64::MAX));
+        assert_eq!(arithmetic("x := -9223372036854775808"), *num(i64::MIN));
+
+        let error = parse("x := 9223372036854775808").unwrap_err();
+        assert_eq!(
+            error.message,
+            "integer literal `9223372036854775808` is too large"
+        );
+        assert_eq!(error.span.column, 6);
+        let error = parse("x := -9223372036854775809").unwrap_err();
+        assert_eq!(
+            error.message,
+            "integer literal `-9223372036854775809` is too large"
+        );
+    }
+
+    #[test]
+    fn assignments_are_arithmetic_unless_only_boolean_fits() {
+        let is_arithmetic =
+            |source| matches!(right_hand_side(source), Expression::ArithmeticExpression(_));
+
+        assert!(is_arithmetic("x := a + 1"));
+        assert!(is_arithmetic("x := (a + 1); y := 2"));
+        assert!(is_arithmetic("x := a; y := true"));
+        assert!(!is_arithmetic("x := true"));
+        assert!(!is_arithmetic("x := a < b"));
+        assert!(!is_arithmetic("x := (a + 1) > b"));
+        assert!(!is_arithmetic("x := not (a = b) and true"));
+    }
+
+    #[test]
+    fn trailing_semicolon_expects_a_statement() {
+        let error = parse("x := 1; y := 2;").unwrap_err();
+        assert_eq!(error.message, "expected a statement, found end of input");
+        assert_eq!((error.span.line, error.span.column), (1, 16));
+    }
+
+    #[test]
+    fn end_of_input_is_reported_after_the_last_token() {
+        let error = parse("x := 1;\n").unwrap_err();
+        assert_eq!((error.span.line, error.span.column), (1, 8));
+
+        let error = parse("x := 1;\n  y := \n").unwrap_err();
+        assert_eq!((error.span.line, error.span.column), (2, 7));
+    }
+}
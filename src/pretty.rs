@@ -0,0 +1,344 @@
+use std::fmt;
+
+use crate::abstract_syntax::{
+    AddExp, AndExp, ArithmeticExpression, AssignmentStmt, Block, BooleanExpression, Condition,
+    DivExp, EqExp, Expression, GEqExp, GTExp, IfElseStmt, LEqExp, LTExp, MulExp, NotExp, NumExp,
+    OrExp, SequenceStmt, SkipStmt, Statement, SubExp, VarExp, WhileStmt,
+};
+
+/*
+   Renders programs in the labelled notation of Nielson, Nielson & Hankin:
+
+   [x := a+b]^1;
+   while [y>a+b]^2 do (
+       [a := a+1]^3;
+       [x := a+b]^4
+   )
+
+   The alternate flag (`{:#}`) leaves the labels out, producing source that
+   `parser::parse` reads back into the same tree. Parentheses are only emitted
+   where the left-associative grammar needs them.
+*/
+
+const INDENT: &str = "    ";
+
+/// Binding strength of each operator, higher binds tighter
+fn precedence_ae(exp: &ArithmeticExpression) -> u8 {
+    return match exp {
+        ArithmeticExpression::AddExp(_) | ArithmeticExpression::SubExp(_) => 1,
+        ArithmeticExpression::MulExp(_) | ArithmeticExpression::DivExp(_) => 2,
+        ArithmeticExpression::VarExp(_) | ArithmeticExpression::NumExp(_) => 3,
+    };
+}
+
+fn precedence_be(exp: &BooleanExpression) -> u8 {
+    return match exp {
+        BooleanExpression::OrExp(_) => 1,
+        BooleanExpression::AndExp(_) => 2,
+        _ => 3,
+    };
+}
+
+fn write_ae_operand(
+    f: &mut fmt::Formatter<'_>,
+    operand: &ArithmeticExpression,
+    parent: u8,
+    right: bool,
+) -> fmt::Result {
+    let own = precedence_ae(operand);
+    if own < parent || (right && own == parent) {
+        return write!(f, "({})", operand);
+    }
+    return write!(f, "{}", operand);
+}
+
+fn write_ae_binary(
+    f: &mut fmt::Formatter<'_>,
+    exp: &ArithmeticExpression,
+    left: &ArithmeticExpression,
+    operator: &str,
+    right: &ArithmeticExpression,
+) -> fmt::Result {
+    let parent = precedence_ae(exp);
+    write_ae_operand(f, left, parent, false)?;
+    write!(f, "{}", operator)?;
+    return write_ae_operand(f, right, parent, true);
+}
+
+fn write_be_operand(
+    f: &mut fmt::Formatter<'_>,
+    operand: &BooleanExpression,
+    parent: u8,
+    right: bool,
+) -> fmt::Result {
+    let own = precedence_be(operand);
+    if own < parent || (right && own == parent) {
+        return write!(f, "({})", operand);
+    }
+    return write!(f, "{}", operand);
+}
+
+impl fmt::Display for ArithmeticExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            ArithmeticExpression::VarExp(VarExp { name }) => write!(f, "{}", name),
+            ArithmeticExpression::NumExp(NumExp { value }) => write!(f, "{}", value),
+            ArithmeticExpression::AddExp(AddExp { left, right }) => {
+                write_ae_binary(f, self, left, "+", right)
+            }
+            ArithmeticExpression::SubExp(SubExp { left, right }) => {
+                write_ae_binary(f, self, left, "-", right)
+            }
+            ArithmeticExpression::MulExp(MulExp { left, right }) => {
+                write_ae_binary(f, self, left, "*", right)
+            }
+            ArithmeticExpression::DivExp(DivExp { left, right }) => {
+                write_ae_binary(f, self, left, "/", right)
+            }
+        };
+    }
+}
+
+impl fmt::Display for BooleanExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parent = precedence_be(self);
+        return match self {
+            BooleanExpression::CTrue(_) => write!(f, "true"),
+            BooleanExpression::CFalse(_) => write!(f, "false"),
+            BooleanExpression::NotExp(NotExp { exp }) => {
+                write!(f, "not ")?;
+                write_be_operand(f, exp, parent, false)
+            }
+            BooleanExpression::AndExp(AndExp { left, right }) => {
+                write_be_operand(f, left, parent, false)?;
+                write!(f, " and ")?;
+                write_be_operand(f, right, parent, true)
+            }
+            BooleanExpression::OrExp(OrExp { left, right }) => {
+                write_be_operand(f, left, parent, false)?;
+                write!(f, " or ")?;
+                write_be_operand(f, right, parent, true)
+            }
+            BooleanExpression::EqExp(EqExp { left, right }) => write!(f, "{}={}", left, right),
+            BooleanExpression::GTExp(GTExp { left, right }) => write!(f, "{}>{}", left, right),
+            BooleanExpression::LTExp(LTExp { left, right }) => write!(f, "{}<{}", left, right),
+            BooleanExpression::GEqExp(GEqExp { left, right }) => write!(f, "{}>={}", left, right),
+            BooleanExpression::LEqExp(LEqExp { left, right }) => write!(f, "{}<={}", left, right),
+        };
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Expression::ArithmeticExpression(exp) => write!(f, "{}", exp),
+            Expression::BooleanExpression(exp) => write!(f, "{}", exp),
+        };
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return write!(f, "{}", self.exp);
+        }
+        return write!(f, "[{}]^{}", self.exp, self.label);
+    }
+}
+
+impl fmt::Display for AssignmentStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return write!(f, "{} := {}", self.name, self.exp);
+        }
+        return write!(f, "[{} := {}]^{}", self.name, self.exp, self.label);
+    }
+}
+
+impl fmt::Display for SkipStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return write!(f, "skip");
+        }
+        return write!(f, "[skip]^{}", self.label);
+    }
+}
+
+impl fmt::Display for Block {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let labelled = !f.alternate();
+        return match self {
+            Block::AssignmentStmt(data) => write_labelled(f, data, labelled),
+            Block::SkipStmt(data) => write_labelled(f, data, labelled),
+            Block::Condition(data) => write_labelled(f, data, labelled),
+        };
+    }
+}
+
+impl fmt::Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let labelled = !f.alternate();
+        return write_stmt(f, self, 0, labelled);
+    }
+}
+
+fn write_labelled<T: fmt::Display>(
+    f: &mut fmt::Formatter<'_>,
+    item: &T,
+    labelled: bool,
+) -> fmt::Result {
+    if labelled {
+        return write!(f, "{}", item);
+    }
+    return write!(f, "{:#}", item);
+}
+
+/// Writes a branch or loop body, opening an indented parenthesised block when
+/// the body is a sequence and would otherwise swallow the statements after it
+fn write_body(
+    f: &mut fmt::Formatter<'_>,
+    stmt: &Statement,
+    indent: usize,
+    labelled: bool,
+) -> fmt::Result {
+    if let Statement::SequenceStmt(_) = stmt {
+        writeln!(f, "(")?;
+        write!(f, "{}", INDENT.repeat(indent + 1))?;
+        write_stmt(f, stmt, indent + 1, labelled)?;
+        writeln!(f)?;
+        return write!(f, "{})", INDENT.repeat(indent));
+    }
+    return write_stmt(f, stmt, indent, labelled);
+}
+
+fn write_stmt(
+    f: &mut fmt::Formatter<'_>,
+    stmt: &Statement,
+    indent: usize,
+    labelled: bool,
+) -> fmt::Result {
+    return match stmt {
+        Statement::AssignmentStmt(data) => write_labelled(f, data, labelled),
+        Statement::SkipStmt(data) => write_labelled(f, data, labelled),
+        Statement::SequenceStmt(SequenceStmt { s1, s2 }) => {
+            // A sequence on the left must be grouped to keep `;` right-nested
+            if let Statement::SequenceStmt(_) = **s1 {
+                write!(f, "(")?;
+                write_stmt(f, s1, indent, labelled)?;
+                write!(f, ")")?;
+            } else {
+                write_stmt(f, s1, indent, labelled)?;
+            }
+            writeln!(f, ";")?;
+            write!(f, "{}", INDENT.repeat(indent))?;
+            write_stmt(f, s2, indent, labelled)
+        }
+        Statement::IfElseStmt(IfElseStmt {
+            condition,
+            then_stmt,
+            else_stmt,
+        }) => {
+            write!(f, "if ")?;
+            write_labelled(f, condition, labelled)?;
+            write!(f, " then ")?;
+            write_body(f, then_stmt, indent, labelled)?;
+            write!(f, " else ")?;
+            write_body(f, else_stmt, indent, labelled)
+        }
+        Statement::WhileStmt(WhileStmt { condition, stmt }) => {
+            write!(f, "while ")?;
+            write_labelled(f, condition, labelled)?;
+            write!(f, " do ")?;
+            write_body(f, stmt, indent, labelled)
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::parse;
+
+    fn unlabelled(source: &str) -> String {
+        return format!("{:#}", parse(source).unwrap());
+    }
+
+    #[test]
+    fn parentheses_only_for_right_nesting() {
+        assert_eq!(unlabelled("x := (a - b) - c"), "x := a-b-c");
+        assert_eq!(unlabelled("x := a - (b - c)"), "x := a-(b-c)");
+        assert_eq!(unlabelled("x := (a / b) / c"), "x := a/b/c");
+        assert_eq!(unlabelled("x := a / (b / c)"), "x := a/(b/c)");
+        assert_eq!(unlabelled("x := (a + b) * c"), "x := (a+b)*c");
+        assert_eq!(unlabelled("x := a + (b * c)"), "x := a+b*c");
+    }
+
+    #[test]
+    fn parentheses_for_boolean_operators() {
+        assert_eq!(
+            unlabelled("x := (a = 1 and b = 2) and c = 3"),
+            "x := a=1 and b=2 and c=3"
+        );
+        assert_eq!(
+            unlabelled("x := a = 1 and (b = 2 and c = 3)"),
+            "x := a=1 and (b=2 and c=3)"
+        );
+        assert_eq!(
+            unlabelled("x := (true or false) or true"),
+            "x := true or false or true"
+        );
+        assert_eq!(
+            unlabelled("x := true or (false or true)"),
+            "x := true or (false or true)"
+        );
+        assert_eq!(
+            unlabelled("x := (true or false) and true"),
+            "x := (true or false) and true"
+        );
+    }
+
+    #[test]
+    fn not_parenthesises_binary_operands() {
+        assert_eq!(
+            unlabelled("x := not (true and false)"),
+            "x := not (true and false)"
+        );
+        assert_eq!(
+            unlabelled("x := not (a < 1 or b < 2)"),
+            "x := not (a<1 or b<2)"
+        );
+        assert_eq!(unlabelled("x := not a < 1"), "x := not a<1");
+        assert_eq!(
+            unlabelled("x := (not true) and false"),
+            "x := not true and false"
+        );
+    }
+
+    #[test]
+    fn sequences_in_bodies_are_indented() {
+        let source = "while x > 1 do (y := x * y; if y > 2 then (x := 1; skip) else skip); z := y";
+        assert_eq!(
+            unlabelled(source),
+            "\
+while x>1 do (
+    y := x*y;
+    if y>2 then (
+        x := 1;
+        skip
+    ) else skip
+);
+z := y"
+        );
+        assert_eq!(
+            format!("{}", parse(source).unwrap()),
+            "\
+while [x>1]^1 do (
+    [y := x*y]^2;
+    if [y>2]^3 then (
+        [x := 1]^4;
+        [skip]^5
+    ) else [skip]^6
+);
+[z := y]^7"
+        );
+    }
+}
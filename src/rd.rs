@@ -1,36 +1,50 @@
-use std::{collections::HashSet, hash::Hash};
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
 
 use crate::{
     abstract_syntax::{AssignmentStmt, Block, Label, Name, Program, UNDEF},
-    framework::{Edge, Framework},
-    utils::{assignments, flow, fv_st, init, union},
+    framework::{self, Framework, GenKill},
+    lattice::PowersetLattice,
+    utils::{assignments, variables},
 };
 
 pub struct ReachingDefinition {
     pub program: Box<Program>,
 }
 
-#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone, Debug, Serialize, Deserialize)]
 pub struct L {
     name: Name,
     label: Label,
 }
 
-impl Framework<L> for ReachingDefinition {
-    fn get_program(&self) -> Box<Program> {
-        return self.program.clone();
+impl L {
+    /// The definition of `name` at `label`, UNDEF for its initial value
+    pub fn new(name: Name, label: Label) -> L {
+        return L { name, label };
     }
+}
 
-    fn get_f(&self) -> Vec<Edge> {
-        flow(self.program.clone())
+/// `(x, l)`, or `(x, ?)` for the initial value
+impl framework::L for L {
+    fn key(&self) -> String {
+        if self.label == UNDEF {
+            return format!("({}, ?)", self.name);
+        }
+        return format!("({}, {})", self.name, self.label);
     }
+}
 
-    fn get_e(&self) -> Vec<Label> {
-        Vec::from([init(self.program.clone())])
+impl Framework<PowersetLattice<L>> for ReachingDefinition {
+    fn get_program(&self) -> Box<Program> {
+        return self.program.clone();
     }
 
+    // Every variable, not just the free ones, starts out with its initial
+    // value: a variable that is only written is still defined before that
     fn get_initial_e(&self) -> HashSet<L> {
-        fv_st(self.program.clone())
+        variables(self.program.clone())
             .into_iter()
             .map(|n| L {
                 name: n,
@@ -39,26 +53,34 @@ impl Framework<L> for ReachingDefinition {
             .collect()
     }
 
-    fn get_initial_others(&self) -> HashSet<L> {
-        HashSet::new()
-    }
+    fn get_lattice(&self) -> PowersetLattice<L> {
+        let undefined = variables(self.program.clone()).into_iter().map(|n| L {
+            name: n,
+            label: UNDEF,
+        });
+        let defined = assignments(self.program.clone()).into_iter().map(|a| L {
+            name: a.name,
+            label: a.label,
+        });
 
-    // set1 está contido no set2
-    fn set_compare(&self, set1: HashSet<L>, set2: HashSet<L>) -> bool {
-        return set1.is_subset(&set2);
+        PowersetLattice {
+            universe: undefined.chain(defined).collect(),
+        }
     }
 
-    // Set union function
-    fn set_union(&self, set1: HashSet<L>, set2: HashSet<L>) -> HashSet<L> {
-        return union(set1, set2);
+    fn fl(&self, block: Box<Block>, entry: HashSet<L>) -> HashSet<L> {
+        return self.fl_gen_kill(block, entry);
     }
+}
 
+impl GenKill<L> for ReachingDefinition {
     fn kill(&self, block: Box<Block>) -> HashSet<L> {
         return match *block {
             Block::AssignmentStmt(AssignmentStmt {
                 name,
                 exp: _,
                 label: _,
+                span: _,
             }) => [
                 Vec::from([L {
                     name: name.to_string(),
@@ -71,9 +93,10 @@ impl Framework<L> for ReachingDefinition {
                              name: _,
                              exp: _,
                              label,
+                             span: _,
                          }| L {
                             name: name.to_string(),
-                            label: label,
+                            label,
                         },
                     )
                     .collect(),
@@ -91,11 +114,27 @@ impl Framework<L> for ReachingDefinition {
                 name,
                 exp: _,
                 label,
-            }) => HashSet::from([L {
-                name: name,
-                label: label,
-            }]),
+                span: _,
+            }) => HashSet::from([L { name, label }]),
             _ => HashSet::new(),
         };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{framework::solve, parser::parse};
+
+    #[test]
+    fn variables_only_written_start_undefined() {
+        let program = parse("x := y; z := x").unwrap();
+        let result = solve(Box::new(ReachingDefinition { program }));
+
+        let z = |label| L::new(String::from("z"), label);
+        assert!(result.entry[&1].contains(&z(UNDEF)));
+        assert!(result.entry[&2].contains(&z(UNDEF)));
+        assert!(result.exit[&2].contains(&z(2)));
+        assert!(!result.exit[&2].contains(&z(UNDEF)));
+    }
+}
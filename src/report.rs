@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+
+use crate::{
+    abstract_syntax::Label,
+    framework::{AnalysisResult, L},
+    stats::SolverStats,
+};
+
+/// Renders the ENTRY and EXIT sets of every label of an analysis result, in
+/// order of the labels and with the elements of every set sorted
+pub fn render_result<E: L>(result: &AnalysisResult<E>) -> String {
+    let mut labels: Vec<&Label> = result.entry.keys().collect();
+    labels.sort();
+
+    let mut out = String::new();
+    for label in labels {
+        out.push_str(&format!("label {}\n", label));
+        out.push_str(&format!("  ENTRY: {}\n", result.entry[label].key()));
+        out.push_str(&format!("  EXIT: {}\n", result.exit[label].key()));
+    }
+
+    return out;
+}
+
+// Renders the work done by the solver, per label and in total
+pub fn render_stats(stats: &SolverStats) -> String {
+    let mut labels: Vec<&Label> = stats
+        .pops
+        .keys()
+        .chain(stats.transfers.keys())
+        .chain(stats.updates.keys())
+        .collect();
+    labels.sort();
+    labels.dedup();
+
+    let count = |counts: &HashMap<Label, usize>, l: &Label| counts.get(l).copied().unwrap_or(0);
+
+    let mut out = String::from("label  pops  transfers  updates\n");
+    for l in labels {
+        out.push_str(&format!(
+            "{:>5}  {:>4}  {:>9}  {:>7}\n",
+            l,
+            count(&stats.pops, l),
+            count(&stats.transfers, l),
+            count(&stats.updates, l)
+        ));
+    }
+    out.push_str(&format!(
+        "total  {:>4}  {:>9}  {:>7}\n",
+        stats.total_pops(),
+        stats.total_transfers(),
+        stats.total_updates()
+    ));
+    out.push_str(&format!("time: {:?}\n", stats.time));
+
+    return out;
+}
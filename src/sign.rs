@@ -0,0 +1,343 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    abstract_syntax::Integer,
+    domain::{Relation, ValueAnalysis, ValueDomain},
+    framework::L,
+    interval::{Bound, Interval, IntervalLattice},
+    lattice::Lattice,
+};
+
+/// Set of possible signs of a value. Arithmetic wraps around on overflow, so
+/// the tables include the signs of wrapped results, see `domain`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Sign {
+    pub negative: bool,
+    pub zero: bool,
+    pub positive: bool,
+}
+
+const NONE: Sign = Sign {
+    negative: false,
+    zero: false,
+    positive: false,
+};
+const NEGATIVE: Sign = Sign {
+    negative: true,
+    ..NONE
+};
+const ZERO: Sign = Sign { zero: true, ..NONE };
+const POSITIVE: Sign = Sign {
+    positive: true,
+    ..NONE
+};
+const ALL: Sign = Sign {
+    negative: true,
+    zero: true,
+    positive: true,
+};
+
+impl Sign {
+    pub fn of(value: Integer) -> Sign {
+        if value < 0 {
+            return NEGATIVE;
+        }
+        if value == 0 {
+            return ZERO;
+        }
+        return POSITIVE;
+    }
+
+    /// The single signs contained in this set
+    fn atoms(self) -> Vec<Sign> {
+        return [
+            (self.negative, NEGATIVE),
+            (self.zero, ZERO),
+            (self.positive, POSITIVE),
+        ]
+        .into_iter()
+        .filter(|(present, _)| *present)
+        .map(|(_, atom)| atom)
+        .collect();
+    }
+
+    fn union(self, other: Sign) -> Sign {
+        return Sign {
+            negative: self.negative || other.negative,
+            zero: self.zero || other.zero,
+            positive: self.positive || other.positive,
+        };
+    }
+
+    fn intersection(self, other: Sign) -> Sign {
+        return Sign {
+            negative: self.negative && other.negative,
+            zero: self.zero && other.zero,
+            positive: self.positive && other.positive,
+        };
+    }
+
+    /// Interval covering a single sign
+    fn interval(self) -> Interval {
+        return match self {
+            NEGATIVE => Interval::new(Bound::NegInf, Bound::Finite(-1)),
+            ZERO => Interval::constant(0),
+            _ => Interval::new(Bound::Finite(1), Bound::PosInf),
+        };
+    }
+
+    /// Applies `operator` to every pair of single signs and joins the results
+    fn combine(self, other: Sign, operator: impl Fn(Sign, Sign) -> Sign) -> Sign {
+        let mut result = NONE;
+        for a1 in self.atoms() {
+            for a2 in other.atoms() {
+                result = result.union(operator(a1, a2));
+            }
+        }
+        return result;
+    }
+}
+
+/// Two positive values wrap around to a negative one at worst, while two
+/// negative ones can wrap to any sign, e.g. `MIN + MIN` to zero
+fn add_atoms(a1: Sign, a2: Sign) -> Sign {
+    if a1 == ZERO {
+        return a2;
+    }
+    if a2 == ZERO {
+        return a1;
+    }
+    if a1 == POSITIVE && a2 == POSITIVE {
+        return POSITIVE.union(NEGATIVE);
+    }
+    return ALL;
+}
+
+/// Operands of opposite signs may wrap around to the other sign, never to
+/// zero, and `0 - Integer::MIN` wraps back to itself
+fn sub_atoms(a1: Sign, a2: Sign) -> Sign {
+    if a2 == ZERO {
+        return a1;
+    }
+    if a1 == ZERO && a2 == POSITIVE {
+        return NEGATIVE;
+    }
+    if a1 == a2 {
+        return ALL;
+    }
+    return POSITIVE.union(NEGATIVE);
+}
+
+/// Two non-zero values may overflow to any sign, e.g. `2^32 * 2^32` to zero
+fn mul_atoms(a1: Sign, a2: Sign) -> Sign {
+    if a1 == ZERO || a2 == ZERO {
+        return ZERO;
+    }
+    return ALL;
+}
+
+/// Truncating division, a quotient of magnitude below one rounds to zero and
+/// `Integer::MIN / -1` wraps back to itself
+fn div_atoms(a1: Sign, a2: Sign) -> Sign {
+    if a2 == ZERO {
+        return NONE;
+    }
+    if a1 == ZERO {
+        return ZERO;
+    }
+    if a1 == NEGATIVE && a2 == NEGATIVE {
+        return ALL;
+    }
+    if a1 == a2 {
+        return POSITIVE.union(ZERO);
+    }
+    return NEGATIVE.union(ZERO);
+}
+
+/// The set of signs, e.g. `{-, 0}`
+impl L for Sign {
+    fn key(&self) -> String {
+        let signs: Vec<&str> = [(self.negative, "-"), (self.zero, "0"), (self.positive, "+")]
+            .into_iter()
+            .filter(|(present, _)| *present)
+            .map(|(_, sign)| sign)
+            .collect();
+        return format!("{{{}}}", signs.join(", "));
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SignLattice {}
+
+impl Lattice for SignLattice {
+    type Element = Sign;
+
+    fn bottom(&self) -> Sign {
+        NONE
+    }
+
+    fn top(&self) -> Sign {
+        ALL
+    }
+
+    fn join(&self, s1: Sign, s2: Sign) -> Sign {
+        return s1.union(s2);
+    }
+
+    fn meet(&self, s1: Sign, s2: Sign) -> Sign {
+        return s1.intersection(s2);
+    }
+
+    fn leq(&self, s1: Sign, s2: Sign) -> bool {
+        return (!s1.negative || s2.negative)
+            && (!s1.zero || s2.zero)
+            && (!s1.positive || s2.positive);
+    }
+}
+
+impl ValueDomain for SignLattice {
+    fn constant(&self, value: Integer) -> Sign {
+        return Sign::of(value);
+    }
+
+    fn add(&self, s1: Sign, s2: Sign) -> Sign {
+        return s1.combine(s2, add_atoms);
+    }
+
+    fn sub(&self, s1: Sign, s2: Sign) -> Sign {
+        return s1.combine(s2, sub_atoms);
+    }
+
+    fn mul(&self, s1: Sign, s2: Sign) -> Sign {
+        return s1.combine(s2, mul_atoms);
+    }
+
+    fn div(&self, s1: Sign, s2: Sign) -> Sign {
+        return s1.combine(s2, div_atoms);
+    }
+
+    fn restrict(&self, relation: Relation, other: Sign) -> Sign {
+        let mut result = NONE;
+        for a1 in ALL.atoms() {
+            if self.satisfiable(relation, a1, other) {
+                result = result.union(a1);
+            }
+        }
+        return result;
+    }
+
+    fn satisfiable(&self, relation: Relation, left: Sign, right: Sign) -> bool {
+        let intervals = IntervalLattice {};
+        return left.atoms().into_iter().any(|a1| {
+            right
+                .atoms()
+                .into_iter()
+                .any(|a2| intervals.satisfiable(relation, a1.interval(), a2.interval()))
+        });
+    }
+}
+
+pub type SignAnalysis = ValueAnalysis<SignLattice>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sign(signs: &str) -> Sign {
+        return Sign {
+            negative: signs.contains('-'),
+            zero: signs.contains('0'),
+            positive: signs.contains('+'),
+        };
+    }
+
+    // Expected results for every pair of single signs, rows and columns in
+    // the order -, 0, +
+    fn assert_table(operator: impl Fn(Sign, Sign) -> Sign, table: [[&str; 3]; 3]) {
+        let atoms = [NEGATIVE, ZERO, POSITIVE];
+        for (i, a1) in atoms.iter().enumerate() {
+            for (j, a2) in atoms.iter().enumerate() {
+                assert_eq!(
+                    operator(*a1, *a2),
+                    sign(table[i][j]),
+                    "{} {}",
+                    a1.key(),
+                    a2.key()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn addition() {
+        let lattice = SignLattice {};
+        assert_table(
+            |s1, s2| lattice.add(s1, s2),
+            [["-0+", "-", "-0+"], ["-", "0", "+"], ["-0+", "+", "-+"]],
+        );
+    }
+
+    #[test]
+    fn subtraction() {
+        let lattice = SignLattice {};
+        assert_table(
+            |s1, s2| lattice.sub(s1, s2),
+            [["-0+", "-", "-+"], ["-+", "0", "-"], ["-+", "+", "-0+"]],
+        );
+    }
+
+    #[test]
+    fn multiplication() {
+        let lattice = SignLattice {};
+        assert_table(
+            |s1, s2| lattice.mul(s1, s2),
+            [["-0+", "0", "-0+"], ["0", "0", "0"], ["-0+", "0", "-0+"]],
+        );
+    }
+
+    #[test]
+    fn division() {
+        let lattice = SignLattice {};
+        assert_table(
+            |s1, s2| lattice.div(s1, s2),
+            [["-0+", "", "-0"], ["0", "", "0"], ["-0", "", "0+"]],
+        );
+    }
+
+    #[test]
+    fn wrapped_results_are_included() {
+        let lattice = SignLattice {};
+        let includes = |result: Sign, value: Integer| lattice.leq(Sign::of(value), result);
+
+        let (max, min) = (Integer::MAX, Integer::MIN);
+        let (zero, big): (Integer, Integer) = (0, 1 << 32);
+        assert!(includes(
+            lattice.add(Sign::of(max), Sign::of(max)),
+            max.wrapping_add(max)
+        ));
+        assert!(includes(
+            lattice.add(Sign::of(min), Sign::of(min)),
+            min.wrapping_add(min)
+        ));
+        assert!(includes(
+            lattice.sub(Sign::of(zero), Sign::of(min)),
+            zero.wrapping_sub(min)
+        ));
+        assert!(includes(
+            lattice.sub(Sign::of(max), Sign::of(-1)),
+            max.wrapping_sub(-1)
+        ));
+        assert!(includes(
+            lattice.sub(Sign::of(min), Sign::of(1)),
+            min.wrapping_sub(1)
+        ));
+        assert!(includes(
+            lattice.mul(Sign::of(big), Sign::of(big)),
+            big.wrapping_mul(big)
+        ));
+        assert!(includes(
+            lattice.div(Sign::of(min), Sign::of(-1)),
+            min.wrapping_div(-1)
+        ));
+    }
+}
@@ -0,0 +1,348 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use crate::{
+    abstract_syntax::{
+        ArithmeticExpression, AssignmentStmt, Block, Condition, Label, Name, Program, UNDEF,
+    },
+    ae::AvailableExpressions,
+    cfg::Cfg,
+    diagnostics::{Diagnostic, Severity},
+    framework::{solve, AnalysisResult},
+    interpreter::{run, Execution, Outcome, Store},
+    lv::LiveVariables,
+    rd::{ReachingDefinition, L},
+    utils::{complex_expressions_c, complex_expressions_e, fv_ae, fv_be, fv_e, variables},
+};
+
+/*
+   Checks analysis results against a concrete execution. At every step of the
+   trace the property an analysis claims for the executed label must hold for
+   the run:
+
+   - reaching definitions contain the label that last wrote every bound
+     variable, UNDEF for variables still holding their initial value
+   - live variables contain every variable read later in the run before it is
+     written again
+   - available expressions were all computed earlier in the run and none of
+     their variables has been written since
+
+   A run is a single path, so this can only expose unsound results, never
+   imprecise ones. The EXIT side of a block whose evaluation failed is not
+   checked, as the run never got there.
+*/
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub analysis: &'static str,
+    pub step: usize, // Position in the execution trace
+    pub label: Label,
+    pub message: String,
+}
+
+impl Violation {
+    /// Points the violation at the block of `program` it was found at
+    pub fn to_diagnostic(&self, program: Box<Program>) -> Diagnostic {
+        return Diagnostic::at_label(
+            Severity::Error,
+            format!("{}: {} (step {})", self.analysis, self.message, self.step),
+            program,
+            self.label,
+        );
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(
+            f,
+            "{}: {} at label {} (step {})",
+            self.analysis, self.message, self.label, self.step
+        );
+    }
+}
+
+// Number of steps of the trace whose block ran to completion
+fn completed(execution: &Execution) -> usize {
+    return match execution.outcome {
+        Outcome::Terminated | Outcome::StepLimit => execution.trace.len(),
+        _ => execution.trace.len() - 1,
+    };
+}
+
+fn assigned(block: &Block) -> Option<&Name> {
+    return match block {
+        Block::AssignmentStmt(AssignmentStmt { name, .. }) => Some(name),
+        _ => None,
+    };
+}
+
+fn reads(block: &Block) -> HashSet<Name> {
+    return match block {
+        Block::AssignmentStmt(AssignmentStmt { exp, .. }) => fv_e(exp.clone()),
+        Block::Condition(Condition { exp, .. }) => fv_be(exp.clone()),
+        Block::SkipStmt(_) => HashSet::new(),
+    };
+}
+
+fn computed(block: &Block) -> HashSet<ArithmeticExpression> {
+    return match block {
+        Block::AssignmentStmt(AssignmentStmt { exp, .. }) => complex_expressions_e(exp.clone()),
+        Block::Condition(condition) => complex_expressions_c(Box::new(condition.clone())),
+        Block::SkipStmt(_) => HashSet::new(),
+    };
+}
+
+pub fn check_reaching_definitions(
+    program: Box<Program>,
+    initial: &Store,
+    execution: &Execution,
+    result: &AnalysisResult<HashSet<L>>,
+) -> Vec<Violation> {
+    let cfg = Cfg::new(program.clone());
+    let mut violations = Vec::new();
+
+    // Bound variables the program never mentions are outside the analysis
+    let variables = variables(program);
+    let mut writers: HashMap<Name, Label> = initial
+        .keys()
+        .filter(|x| variables.contains(*x))
+        .map(|x| (x.clone(), UNDEF))
+        .collect();
+
+    let mut check = |step: usize,
+                     l: Label,
+                     side: &str,
+                     claimed: &HashSet<L>,
+                     writers: &HashMap<Name, Label>| {
+        let mut missing: Vec<(&Name, &Label)> = writers
+            .iter()
+            .filter(|(x, w)| !claimed.contains(&L::new((*x).clone(), **w)))
+            .collect();
+        missing.sort();
+        for (x, w) in missing {
+            let w = if *w == UNDEF {
+                String::from("?")
+            } else {
+                w.to_string()
+            };
+            violations.push(Violation {
+                analysis: "reaching definitions",
+                step,
+                label: l,
+                message: format!("{} misses ({}, {})", side, x, w),
+            });
+        }
+    };
+
+    for (step, l) in execution.trace.iter().enumerate() {
+        check(step, *l, "entry", &result.entry[l], &writers);
+        if step == completed(execution) {
+            break;
+        }
+
+        if let Some(x) = assigned(&cfg.block(*l)) {
+            writers.insert(x.clone(), *l);
+        }
+        check(step, *l, "exit", &result.exit[l], &writers);
+    }
+
+    return violations;
+}
+
+pub fn check_live_variables(
+    program: Box<Program>,
+    execution: &Execution,
+    result: &AnalysisResult<HashSet<Name>>,
+) -> Vec<Violation> {
+    let cfg = Cfg::new(program);
+    let mut violations = Vec::new();
+
+    let mut check =
+        |step: usize, l: Label, side: &str, claimed: &HashSet<Name>, live: &HashSet<Name>| {
+            let mut missing: Vec<&Name> = live.difference(claimed).collect();
+            missing.sort();
+            for x in missing {
+                violations.push(Violation {
+                    analysis: "live variables",
+                    step,
+                    label: l,
+                    message: format!("{} misses {}, which is read later", side, x),
+                });
+            }
+        };
+
+    // Walk the trace backwards, `live` holds the variables read after the
+    // current step before being written
+    let mut live: HashSet<Name> = HashSet::new();
+    for (step, l) in execution.trace.iter().enumerate().rev() {
+        let block = cfg.block(*l);
+        if step < completed(execution) {
+            check(step, *l, "exit", &result.exit[l], &live);
+            if let Some(x) = assigned(&block) {
+                live.remove(x);
+            }
+        }
+        live.extend(reads(&block));
+        check(step, *l, "entry", &result.entry[l], &live);
+    }
+
+    // Reported in the order of the trace
+    violations.reverse();
+    return violations;
+}
+
+pub fn check_available_expressions(
+    program: Box<Program>,
+    execution: &Execution,
+    result: &AnalysisResult<HashSet<ArithmeticExpression>>,
+) -> Vec<Violation> {
+    let cfg = Cfg::new(program);
+    let mut violations = Vec::new();
+
+    let mut check = |step: usize,
+                     l: Label,
+                     side: &str,
+                     claimed: &HashSet<ArithmeticExpression>,
+                     available: &HashSet<ArithmeticExpression>| {
+        let mut wrong: Vec<String> = claimed
+            .difference(available)
+            .map(|e| e.to_string())
+            .collect();
+        wrong.sort();
+        for e in wrong {
+            violations.push(Violation {
+                analysis: "available expressions",
+                step,
+                label: l,
+                message: format!("{} claims {}, which is not available", side, e),
+            });
+        }
+    };
+
+    let mut available: HashSet<ArithmeticExpression> = HashSet::new();
+    for (step, l) in execution.trace.iter().enumerate() {
+        check(step, *l, "entry", &result.entry[l], &available);
+        if step == completed(execution) {
+            break;
+        }
+
+        let block = cfg.block(*l);
+        available.extend(computed(&block));
+        if let Some(x) = assigned(&block) {
+            available.retain(|e| !fv_ae(Box::new(e.clone())).contains(x));
+        }
+        check(step, *l, "exit", &result.exit[l], &available);
+    }
+
+    return violations;
+}
+
+/// Runs `program` from `store` and checks the results of reaching
+/// definitions, live variables and available expressions against the run
+pub fn check_soundness(program: Box<Program>, store: Store, step_limit: usize) -> Vec<Violation> {
+    let execution = run(program.clone(), store.clone(), step_limit);
+
+    let rd = solve(Box::new(ReachingDefinition {
+        program: program.clone(),
+    }));
+    let lv = solve(Box::new(LiveVariables {
+        program: program.clone(),
+    }));
+    let ae = solve(Box::new(AvailableExpressions {
+        program: program.clone(),
+    }));
+
+    return [
+        check_reaching_definitions(program.clone(), &store, &execution, &rd),
+        check_live_variables(program.clone(), &execution, &lv),
+        check_available_expressions(program, &execution, &ae),
+    ]
+    .concat();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    // Runs [x := 2]^1; [y := x*3]^2; [z := y+x]^3 from an empty store
+    const SOURCE: &str = "x := 2; y := x * 3; z := y + x";
+
+    fn execution() -> Execution {
+        return run(parse(SOURCE).unwrap(), Store::new(), 100);
+    }
+
+    #[test]
+    fn correct_results_hold() {
+        assert_eq!(
+            check_soundness(parse(SOURCE).unwrap(), Store::new(), 100),
+            []
+        );
+    }
+
+    #[test]
+    fn removed_reaching_definition_is_reported() {
+        let program = parse(SOURCE).unwrap();
+        let mut rd = solve(Box::new(ReachingDefinition {
+            program: program.clone(),
+        }));
+        rd.entry
+            .get_mut(&2)
+            .unwrap()
+            .remove(&L::new(String::from("x"), 1));
+
+        assert_eq!(
+            check_reaching_definitions(program, &Store::new(), &execution(), &rd),
+            [Violation {
+                analysis: "reaching definitions",
+                step: 1,
+                label: 2,
+                message: String::from("entry misses (x, 1)"),
+            }]
+        );
+    }
+
+    #[test]
+    fn removed_live_variable_is_reported() {
+        let program = parse(SOURCE).unwrap();
+        let mut lv = solve(Box::new(LiveVariables {
+            program: program.clone(),
+        }));
+        lv.exit.get_mut(&2).unwrap().remove("x");
+
+        assert_eq!(
+            check_live_variables(program, &execution(), &lv),
+            [Violation {
+                analysis: "live variables",
+                step: 1,
+                label: 2,
+                message: String::from("exit misses x, which is read later"),
+            }]
+        );
+    }
+
+    #[test]
+    fn claimed_expression_is_reported() {
+        let program = parse(SOURCE).unwrap();
+        let mut ae = solve(Box::new(AvailableExpressions {
+            program: program.clone(),
+        }));
+        // x*3 only becomes available at the exit of 2
+        let computed: Vec<ArithmeticExpression> = ae.exit[&2].iter().cloned().collect();
+        assert_eq!(computed.len(), 1);
+        ae.entry.get_mut(&1).unwrap().insert(computed[0].clone());
+
+        assert_eq!(
+            check_available_expressions(program, &execution(), &ae),
+            [Violation {
+                analysis: "available expressions",
+                step: 0,
+                label: 1,
+                message: String::from("entry claims x*3, which is not available"),
+            }]
+        );
+    }
+}
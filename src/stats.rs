@@ -0,0 +1,72 @@
+use std::{collections::HashMap, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::abstract_syntax::Label;
+
+/// Work done by the solver to reach its fixpoint. Pops are counted for the
+/// source label of the edge taken off the worklist, transfer evaluations for
+/// the label of the transferred block and updates for the label whose value
+/// grew (or shrank while narrowing).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SolverStats {
+    pub pops: HashMap<Label, usize>,
+    pub transfers: HashMap<Label, usize>,
+    pub updates: HashMap<Label, usize>,
+    pub time: Duration,
+}
+
+impl SolverStats {
+    pub fn record_pop(&mut self, l: Label) {
+        *self.pops.entry(l).or_insert(0) += 1;
+    }
+
+    pub fn record_transfer(&mut self, l: Label) {
+        *self.transfers.entry(l).or_insert(0) += 1;
+    }
+
+    pub fn record_update(&mut self, l: Label) {
+        *self.updates.entry(l).or_insert(0) += 1;
+    }
+
+    pub fn total_pops(&self) -> usize {
+        return self.pops.values().sum();
+    }
+
+    pub fn total_transfers(&self) -> usize {
+        return self.transfers.values().sum();
+    }
+
+    pub fn total_updates(&self) -> usize {
+        return self.updates.values().sum();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{framework::solve_with, parser::parse, rd::ReachingDefinition, worklist::Strategy};
+
+    fn sorted(counts: &HashMap<Label, usize>) -> Vec<(Label, usize)> {
+        let mut counts: Vec<(Label, usize)> = counts.iter().map(|(l, n)| (*l, *n)).collect();
+        counts.sort();
+        return counts;
+    }
+
+    #[test]
+    fn counts_of_a_loop() {
+        // The worklist starts as (2,3), (3,2), (1,2). Popping (3,2) and (1,2)
+        // each grow ENTRY(2) and queue (2,3), whose first pop grows ENTRY(3)
+        // and queues (3,2). The other three pops change nothing.
+        let program = parse("x := 1; while x < 3 do x := x + 1").unwrap();
+        let result = solve_with(Box::new(ReachingDefinition { program }), Strategy::Fifo);
+        let stats = result.stats;
+
+        assert_eq!(sorted(&stats.pops), [(1, 1), (2, 3), (3, 2)]);
+        assert_eq!(sorted(&stats.transfers), [(1, 1), (2, 3), (3, 2)]);
+        assert_eq!(sorted(&stats.updates), [(2, 2), (3, 1)]);
+        assert_eq!(stats.total_pops(), 6);
+        assert_eq!(stats.total_transfers(), 6);
+        assert_eq!(stats.total_updates(), 3);
+    }
+}
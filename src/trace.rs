@@ -0,0 +1,221 @@
+use crate::{
+    abstract_syntax::Label,
+    framework::{Edge, L},
+};
+
+/*
+   Iteration trace of the worklist solver, laid out like the tables of
+   Nielson, Nielson & Hankin: one row per step with the worklist W before the
+   step, the edge taken off it and the values of Analysis afterwards.
+
+   step  W               edge    A(1)  A(2)  ...
+   0     (1,2), (2,3)            {}    {}
+   1     (1,2), (2,3)    (1,2)   {}    {x}*
+   ...
+
+   The value updated in a step is marked with `*` in text and set in bold in
+   LaTeX. Narrowing passes run after the worklist is empty are not traced.
+*/
+
+#[derive(Debug, Clone)]
+pub struct TraceStep<E> {
+    pub worklist: Vec<Edge>,
+    pub edge: Option<Edge>,
+    pub updated: Option<Label>,
+    pub analysis: Vec<E>, // Indexed like `Trace::labels`
+}
+
+#[derive(Debug, Clone)]
+pub struct Trace<E> {
+    pub labels: Vec<Label>,
+    pub steps: Vec<TraceStep<E>>,
+}
+
+fn render_edges(edges: &[Edge]) -> String {
+    return edges
+        .iter()
+        .map(|(l1, l2)| format!("({},{})", l1, l2))
+        .collect::<Vec<String>>()
+        .join(", ");
+}
+
+// A table cell and whether it holds the value updated in its step
+type Cell = (String, bool);
+
+impl<E: L> Trace<E> {
+    // Cells of the table, the header row first
+    fn cells(&self) -> Vec<Vec<Cell>> {
+        let mut header = Vec::from([
+            (String::from("step"), false),
+            (String::from("W"), false),
+            (String::from("edge"), false),
+        ]);
+        for l in &self.labels {
+            header.push((format!("A({})", l), false));
+        }
+
+        let mut rows = Vec::from([header]);
+        for (i, step) in self.steps.iter().enumerate() {
+            let mut row = Vec::from([
+                (i.to_string(), false),
+                (render_edges(&step.worklist), false),
+                (
+                    step.edge
+                        .map_or(String::new(), |edge| render_edges(&[edge])),
+                    false,
+                ),
+            ]);
+            for (l, value) in self.labels.iter().zip(&step.analysis) {
+                row.push((value.key(), step.updated == Some(*l)));
+            }
+            rows.push(row);
+        }
+
+        return rows;
+    }
+
+    pub fn to_text(&self) -> String {
+        let rows: Vec<Vec<String>> = self
+            .cells()
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|(cell, updated)| if updated { cell + "*" } else { cell })
+                    .collect()
+            })
+            .collect();
+
+        // Cells hold ⊥, ⊤ and ∞, so widths are counted in characters, not bytes
+        let mut widths = Vec::new();
+        for column in 0..rows[0].len() {
+            widths.push(
+                rows.iter()
+                    .map(|row| row[column].chars().count())
+                    .max()
+                    .unwrap(),
+            );
+        }
+
+        let mut out = String::new();
+        for row in rows {
+            let line: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| {
+                    format!("{}{}", cell, " ".repeat(width - cell.chars().count()))
+                })
+                .collect();
+            out.push_str(line.join("  ").trim_end());
+            out.push('\n');
+        }
+        return out;
+    }
+
+    pub fn to_latex(&self) -> String {
+        let mut out = format!(
+            "\\begin{{tabular}}{{r|l|l|{}}}\n",
+            vec!["l"; self.labels.len()].join("|")
+        );
+        for (i, row) in self.cells().into_iter().enumerate() {
+            let line: Vec<String> = row
+                .into_iter()
+                .map(|(cell, updated)| {
+                    if i == 0 {
+                        latex_escape(&cell)
+                    } else if updated {
+                        format!("\\textbf{{\\texttt{{{}}}}}", latex_escape(&cell))
+                    } else {
+                        format!("\\texttt{{{}}}", latex_escape(&cell))
+                    }
+                })
+                .collect();
+            out.push_str(&format!("  {} \\\\\n", line.join(" & ")));
+            if i == 0 {
+                out.push_str("  \\hline\n");
+            }
+        }
+        out.push_str("\\end{tabular}\n");
+        return out;
+    }
+}
+
+fn latex_escape(text: &str) -> String {
+    let mut out = String::new();
+    for c in text.chars() {
+        match c {
+            '{' | '}' | '_' | '&' | '%' | '$' | '#' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\\' => out.push_str("\\textbackslash{}"),
+            '~' => out.push_str("\\textasciitilde{}"),
+            '^' => out.push_str("\\textasciicircum{}"),
+            '⊥' => out.push_str("$\\bot$"),
+            '⊤' => out.push_str("$\\top$"),
+            '∞' => out.push_str("$\\infty$"),
+            _ => out.push(c),
+        }
+    }
+    return out;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cp::Constant, framework::solve_traced, parser::parse, rd::ReachingDefinition,
+        worklist::Strategy,
+    };
+
+    const SOURCE: &str = "x := 1; y := x";
+
+    #[test]
+    fn text_table_of_reaching_definitions() {
+        let program = parse(SOURCE).unwrap();
+        let (_, trace) = solve_traced(Box::new(ReachingDefinition { program }), Strategy::Lifo);
+        assert_eq!(
+            trace.to_text(),
+            "\
+step  W      edge   A(1)              A(2)
+0     (1,2)         {(x, ?), (y, ?)}  {}
+1     (1,2)  (1,2)  {(x, ?), (y, ?)}  {(x, 1), (y, ?)}*
+"
+        );
+    }
+
+    #[test]
+    fn latex_table_of_reaching_definitions() {
+        let program = parse(SOURCE).unwrap();
+        let (_, trace) = solve_traced(Box::new(ReachingDefinition { program }), Strategy::Lifo);
+        assert_eq!(
+            trace.to_latex(),
+            r"\begin{tabular}{r|l|l|l|l}
+  step & W & edge & A(1) & A(2) \\
+  \hline
+  \texttt{0} & \texttt{(1,2)} & \texttt{} & \texttt{\{(x, ?), (y, ?)\}} & \texttt{\{\}} \\
+  \texttt{1} & \texttt{(1,2)} & \texttt{(1,2)} & \texttt{\{(x, ?), (y, ?)\}} & \textbf{\texttt{\{(x, 1), (y, ?)\}}} \\
+\end{tabular}
+"
+        );
+    }
+
+    #[test]
+    fn symbols_count_as_one_character() {
+        let trace = Trace {
+            labels: Vec::from([1, 2]),
+            steps: Vec::from([TraceStep {
+                worklist: Vec::new(),
+                edge: None,
+                updated: Some(2),
+                analysis: Vec::from([Constant::Top, Constant::Bottom]),
+            }]),
+        };
+        assert_eq!(
+            trace.to_text(),
+            "step  W  edge  A(1)  A(2)\n0              ⊤     ⊥*\n"
+        );
+        assert!(trace
+            .to_latex()
+            .contains(r"\texttt{$\top$} & \textbf{\texttt{$\bot$}}"));
+    }
+}
@@ -3,7 +3,8 @@ use std::{collections::HashSet, hash::Hash};
 use crate::abstract_syntax::{
     AddExp, AndExp, ArithmeticExpression, AssignmentStmt, Block, BooleanExpression, CFalse, CTrue,
     Condition, DivExp, EqExp, Expression, GEqExp, GTExp, IfElseStmt, LEqExp, LTExp, Label, MulExp,
-    Name, NotExp, NumExp, OrExp, SequenceStmt, SkipStmt, Statement, SubExp, VarExp, WhileStmt,
+    Name, NotExp, NumExp, OrExp, SequenceStmt, SkipStmt, Span, Statement, SubExp, VarExp,
+    WhileStmt,
 };
 
 pub fn blocks(stmt: Box<Statement>) -> Vec<Box<Block>> {
@@ -76,21 +77,40 @@ pub fn fv_st(stmt: Box<Statement>) -> HashSet<Name> {
             name: _,
             exp,
             label: _,
+            span: _,
         }) => fv_e(exp),
-        Statement::SkipStmt(SkipStmt { label: _ }) => HashSet::new(),
+        Statement::SkipStmt(SkipStmt { label: _, span: _ }) => HashSet::new(),
         Statement::SequenceStmt(SequenceStmt { s1, s2 }) => union(fv_st(s1), fv_st(s2)),
         Statement::IfElseStmt(IfElseStmt {
-            condition: Condition { exp, label: _ },
+            condition:
+                Condition {
+                    exp,
+                    label: _,
+                    span: _,
+                },
             then_stmt,
             else_stmt,
         }) => union(union(fv_be(exp), fv_st(then_stmt)), fv_st(else_stmt)),
         Statement::WhileStmt(WhileStmt {
-            condition: Condition { exp, label: _ },
+            condition:
+                Condition {
+                    exp,
+                    label: _,
+                    span: _,
+                },
             stmt,
         }) => union(fv_be(exp), fv_st(stmt)),
     };
 }
 
+/// Every variable read or written by the statement
+pub fn variables(stmt: Box<Statement>) -> HashSet<Name> {
+    return union(
+        fv_st(stmt.clone()),
+        assignments(stmt).into_iter().map(|a| a.name).collect(),
+    );
+}
+
 type Edge = (Label, Label);
 
 pub fn init(stmt: Box<Statement>) -> Label {
@@ -99,16 +119,27 @@ pub fn init(stmt: Box<Statement>) -> Label {
             name: _,
             exp: _,
             label,
+            span: _,
         }) => label,
-        Statement::SkipStmt(SkipStmt { label }) => label,
+        Statement::SkipStmt(SkipStmt { label, span: _ }) => label,
         Statement::SequenceStmt(SequenceStmt { s1, s2: _ }) => init(s1),
         Statement::IfElseStmt(IfElseStmt {
-            condition: Condition { exp: _, label },
+            condition:
+                Condition {
+                    exp: _,
+                    label,
+                    span: _,
+                },
             then_stmt: _,
             else_stmt: _,
         }) => label,
         Statement::WhileStmt(WhileStmt {
-            condition: Condition { exp: _, label },
+            condition:
+                Condition {
+                    exp: _,
+                    label,
+                    span: _,
+                },
             stmt: _,
         }) => label,
     };
@@ -120,8 +151,9 @@ pub fn r#final(stmt: Box<Statement>) -> Vec<Label> {
             name: _,
             exp: _,
             label,
+            span: _,
         }) => Vec::from([label]),
-        Statement::SkipStmt(SkipStmt { label }) => Vec::from([label]),
+        Statement::SkipStmt(SkipStmt { label, span: _ }) => Vec::from([label]),
         Statement::SequenceStmt(SequenceStmt { s1: _, s2 }) => r#final(s2),
         Statement::IfElseStmt(IfElseStmt {
             condition: _,
@@ -129,7 +161,12 @@ pub fn r#final(stmt: Box<Statement>) -> Vec<Label> {
             else_stmt,
         }) => [r#final(then_stmt), r#final(else_stmt)].concat(),
         Statement::WhileStmt(WhileStmt {
-            condition: Condition { exp: _, label },
+            condition:
+                Condition {
+                    exp: _,
+                    label,
+                    span: _,
+                },
             stmt: _,
         }) => Vec::from([label]),
     };
@@ -141,14 +178,36 @@ pub fn label(block: Box<Block>) -> Label {
             name: _,
             exp: _,
             label,
+            span: _,
+        }) => label,
+        Block::SkipStmt(SkipStmt { label, span: _ }) => label,
+        Block::Condition(Condition {
+            exp: _,
+            label,
+            span: _,
         }) => label,
-        Block::SkipStmt(SkipStmt { label }) => label,
-        Block::Condition(Condition { exp: _, label }) => label,
+    };
+}
+
+pub fn span(block: Box<Block>) -> Span {
+    return match *block {
+        Block::AssignmentStmt(AssignmentStmt {
+            name: _,
+            exp: _,
+            label: _,
+            span,
+        }) => span,
+        Block::SkipStmt(SkipStmt { label: _, span }) => span,
+        Block::Condition(Condition {
+            exp: _,
+            label: _,
+            span,
+        }) => span,
     };
 }
 
 pub fn labels(stmt: Box<Statement>) -> Vec<Label> {
-    return blocks(stmt).into_iter().map(|b| label(b)).collect();
+    return blocks(stmt).into_iter().map(label).collect();
 }
 
 pub fn flow(stmt: Box<Statement>) -> Vec<Edge> {
@@ -165,7 +224,12 @@ pub fn flow(stmt: Box<Statement>) -> Vec<Edge> {
         ]
         .concat(),
         Statement::IfElseStmt(IfElseStmt {
-            condition: Condition { exp: _, label },
+            condition:
+                Condition {
+                    exp: _,
+                    label,
+                    span: _,
+                },
             then_stmt,
             else_stmt,
         }) => [
@@ -178,7 +242,12 @@ pub fn flow(stmt: Box<Statement>) -> Vec<Edge> {
         ]
         .concat(),
         Statement::WhileStmt(WhileStmt {
-            condition: Condition { exp: _, label },
+            condition:
+                Condition {
+                    exp: _,
+                    label,
+                    span: _,
+                },
             stmt,
         }) => [
             flow(stmt.clone()),
@@ -196,6 +265,67 @@ pub fn flow_r(stmt: Box<Statement>) -> Vec<Edge> {
         .collect();
 }
 
+/// Labels of the loop conditions, the targets of every back edge in `flow`
+pub fn loop_heads(stmt: Box<Statement>) -> Vec<Label> {
+    return match *stmt {
+        Statement::AssignmentStmt(_) => Vec::new(),
+        Statement::SkipStmt(_) => Vec::new(),
+        Statement::SequenceStmt(SequenceStmt { s1, s2 }) => {
+            [loop_heads(s1), loop_heads(s2)].concat()
+        }
+        Statement::IfElseStmt(IfElseStmt {
+            condition: _,
+            then_stmt,
+            else_stmt,
+        }) => [loop_heads(then_stmt), loop_heads(else_stmt)].concat(),
+        Statement::WhileStmt(WhileStmt {
+            condition:
+                Condition {
+                    exp: _,
+                    label,
+                    span: _,
+                },
+            stmt,
+        }) => [Vec::from([label]), loop_heads(stmt)].concat(),
+    };
+}
+
+/// Edges of `flow` leaving a condition that are taken when it holds; every
+/// other edge leaving a condition is taken when it does not
+pub fn true_edges(stmt: Box<Statement>) -> Vec<Edge> {
+    return match *stmt {
+        Statement::AssignmentStmt(_) => Vec::new(),
+        Statement::SkipStmt(_) => Vec::new(),
+        Statement::SequenceStmt(SequenceStmt { s1, s2 }) => {
+            [true_edges(s1), true_edges(s2)].concat()
+        }
+        Statement::IfElseStmt(IfElseStmt {
+            condition:
+                Condition {
+                    exp: _,
+                    label,
+                    span: _,
+                },
+            then_stmt,
+            else_stmt,
+        }) => [
+            Vec::from([(label, init(then_stmt.clone()))]),
+            true_edges(then_stmt),
+            true_edges(else_stmt),
+        ]
+        .concat(),
+        Statement::WhileStmt(WhileStmt {
+            condition:
+                Condition {
+                    exp: _,
+                    label,
+                    span: _,
+                },
+            stmt,
+        }) => [Vec::from([(label, init(stmt.clone()))]), true_edges(stmt)].concat(),
+    };
+}
+
 pub fn union<L: Eq + Hash>(set1: HashSet<L>, set2: HashSet<L>) -> HashSet<L> {
     let mut union = HashSet::new();
 
@@ -315,8 +445,9 @@ pub fn complex_expressions_stmt(stmt: Box<Statement>) -> HashSet<ArithmeticExpre
             name: _,
             exp,
             label: _,
+            span: _,
         }) => complex_expressions_e(exp),
-        Statement::SkipStmt(SkipStmt { label: _ }) => HashSet::new(),
+        Statement::SkipStmt(SkipStmt { label: _, span: _ }) => HashSet::new(),
         Statement::SequenceStmt(SequenceStmt { s1, s2 }) => {
             union(complex_expressions_stmt(s1), complex_expressions_stmt(s2))
         }
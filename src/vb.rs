@@ -1,19 +1,17 @@
 use std::collections::HashSet;
 
 use crate::{
-    abstract_syntax::{ArithmeticExpression, AssignmentStmt, Block, Condition, Label, Program},
-    framework::{Edge, Framework},
-    utils::{
-        complex_expressions_be, complex_expressions_e, complex_expressions_stmt, flow_r, fv_ae,
-        intersection, r#final,
-    },
+    abstract_syntax::{ArithmeticExpression, AssignmentStmt, Block, Condition, Program},
+    framework::{Framework, GenKill},
+    lattice::ReversePowersetLattice,
+    utils::{complex_expressions_be, complex_expressions_e, complex_expressions_stmt, fv_ae},
 };
 
 pub struct VeryBusyExpressions {
     pub program: Box<Program>,
 }
 
-impl Framework<ArithmeticExpression> for VeryBusyExpressions {
+impl Framework<ReversePowersetLattice<ArithmeticExpression>> for VeryBusyExpressions {
     fn get_program(&self) -> Box<Program> {
         return self.program.clone();
     }
@@ -22,46 +20,33 @@ impl Framework<ArithmeticExpression> for VeryBusyExpressions {
         true
     }
 
-    fn get_f(&self) -> Vec<Edge> {
-        flow_r(self.program.clone())
-    }
-
-    fn get_e(&self) -> Vec<Label> {
-        return r#final(self.program.clone());
-    }
-
     fn get_initial_e(&self) -> HashSet<ArithmeticExpression> {
         HashSet::new()
     }
 
-    fn get_initial_others(&self) -> HashSet<ArithmeticExpression> {
-        complex_expressions_stmt(self.program.clone())
-    }
-
-    // set1 está contido no set2
-    fn set_compare(
-        &self,
-        set1: HashSet<ArithmeticExpression>,
-        set2: HashSet<ArithmeticExpression>,
-    ) -> bool {
-        return set2.is_subset(&set1);
+    fn get_lattice(&self) -> ReversePowersetLattice<ArithmeticExpression> {
+        ReversePowersetLattice {
+            universe: complex_expressions_stmt(self.program.clone()),
+        }
     }
 
-    // Set union function
-    fn set_union(
+    fn fl(
         &self,
-        set1: HashSet<ArithmeticExpression>,
-        set2: HashSet<ArithmeticExpression>,
+        block: Box<Block>,
+        entry: HashSet<ArithmeticExpression>,
     ) -> HashSet<ArithmeticExpression> {
-        return intersection(set1, set2);
+        return self.fl_gen_kill(block, entry);
     }
+}
 
+impl GenKill<ArithmeticExpression> for VeryBusyExpressions {
     fn kill(&self, block: Box<Block>) -> HashSet<ArithmeticExpression> {
         return match *block {
             Block::AssignmentStmt(AssignmentStmt {
                 name,
                 exp: _,
                 label: _,
+                span: _,
             }) => complex_expressions_stmt(self.program.clone())
                 .into_iter()
                 .filter(|e| fv_ae(Box::new((*e).clone())).contains(&name))
@@ -76,8 +61,13 @@ impl Framework<ArithmeticExpression> for VeryBusyExpressions {
                 name: _,
                 exp,
                 label: _,
+                span: _,
             }) => complex_expressions_e(exp),
-            Block::Condition(Condition { exp, label: _ }) => complex_expressions_be(exp),
+            Block::Condition(Condition {
+                exp,
+                label: _,
+                span: _,
+            }) => complex_expressions_be(exp),
             _ => HashSet::new(),
         };
     }
@@ -0,0 +1,165 @@
+use std::fmt;
+
+use crate::{
+    abstract_syntax::{Label, Program},
+    diagnostics::{Diagnostic, Severity},
+    framework::{AnalysisResult, Edge, Framework, L},
+    lattice::Lattice,
+};
+
+/*
+   Checks that a result solves the data flow equations of its framework,
+   independently of how it was computed. With A the side of every label the
+   solver iterates on (ENTRY for forward analyses, EXIT for backward ones)
+   and A' the other side:
+
+   - ι ⊑ A(l)                 for every extremal label l
+   - f_l(A(l)) ⊑ A(l')        for every edge (l, l') of F
+   - A'(l) = f_l(A(l))        for every label l
+
+   Any solution passes, not only the least one, so this catches unsound
+   solvers (e.g. comparing in the wrong direction) but not imprecise ones.
+*/
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FixpointError<E> {
+    Missing {
+        label: Label,
+    },
+    Initial {
+        label: Label,
+        initial: E,
+        value: E,
+    },
+    Edge {
+        edge: Edge,
+        transferred: E,
+        target: E,
+    },
+    Transfer {
+        label: Label,
+        transferred: E,
+        value: E,
+    },
+}
+
+impl<E: L> FixpointError<E> {
+    /// Label whose value breaks the equations, for an edge its target
+    pub fn label(&self) -> Label {
+        return match self {
+            FixpointError::Missing { label }
+            | FixpointError::Initial { label, .. }
+            | FixpointError::Transfer { label, .. } => *label,
+            FixpointError::Edge { edge: (_, l2), .. } => *l2,
+        };
+    }
+
+    pub fn to_diagnostic(&self, program: Box<Program>) -> Diagnostic {
+        return Diagnostic::at_label(Severity::Error, self.to_string(), program, self.label());
+    }
+}
+
+impl<E: L> fmt::Display for FixpointError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            FixpointError::Missing { label } => write!(f, "label {} has no value", label),
+            FixpointError::Initial {
+                label,
+                initial,
+                value,
+            } => write!(
+                f,
+                "extremal label {} holds {}, which is not above the initial value {}",
+                label,
+                value.key(),
+                initial.key()
+            ),
+            FixpointError::Edge {
+                edge: (l1, l2),
+                transferred,
+                target,
+            } => write!(
+                f,
+                "edge ({},{}) carries {}, which is not below {}",
+                l1,
+                l2,
+                transferred.key(),
+                target.key()
+            ),
+            FixpointError::Transfer {
+                label,
+                transferred,
+                value,
+            } => write!(
+                f,
+                "transfer function of label {} gives {}, but the result holds {}",
+                label,
+                transferred.key(),
+                value.key()
+            ),
+        };
+    }
+}
+
+pub fn verify_fixpoint<L: Lattice>(
+    framework: &dyn Framework<L>,
+    result: &AnalysisResult<L::Element>,
+) -> Result<(), Vec<FixpointError<L::Element>>> {
+    let lattice = framework.get_lattice();
+    let cfg = framework.get_cfg();
+    let (before, after) = if cfg.is_reversed() {
+        (&result.exit, &result.entry)
+    } else {
+        (&result.entry, &result.exit)
+    };
+
+    let missing: Vec<FixpointError<L::Element>> = cfg
+        .labels()
+        .iter()
+        .filter(|l| !before.contains_key(l) || !after.contains_key(l))
+        .map(|l| FixpointError::Missing { label: *l })
+        .collect();
+    if !missing.is_empty() {
+        return Err(missing);
+    }
+
+    let mut errors = Vec::new();
+
+    let initial = framework.get_initial_e();
+    for l in cfg.extremal() {
+        if !lattice.leq(initial.clone(), before[l].clone()) {
+            errors.push(FixpointError::Initial {
+                label: *l,
+                initial: initial.clone(),
+                value: before[l].clone(),
+            });
+        }
+    }
+
+    for (l1, l2) in cfg.edges() {
+        let transferred = framework.fl_edge((*l1, *l2), cfg.block(*l1), before[l1].clone());
+        if !lattice.leq(transferred.clone(), before[l2].clone()) {
+            errors.push(FixpointError::Edge {
+                edge: (*l1, *l2),
+                transferred,
+                target: before[l2].clone(),
+            });
+        }
+    }
+
+    for l in cfg.labels() {
+        let transferred = framework.fl(cfg.block(*l), before[l].clone());
+        if transferred != after[l] {
+            errors.push(FixpointError::Transfer {
+                label: *l,
+                transferred,
+                value: after[l].clone(),
+            });
+        }
+    }
+
+    if errors.is_empty() {
+        return Ok(());
+    }
+    return Err(errors);
+}
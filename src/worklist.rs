@@ -0,0 +1,250 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+};
+
+use crate::{abstract_syntax::Label, cfg::Cfg, framework::Edge};
+
+/// Order in which the solver revisits the edges of the flow graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strategy {
+    /// Queue, edges are processed in the order they were added
+    Fifo,
+    /// Stack, the most recently added edge is processed first
+    #[default]
+    Lifo,
+    /// Priority queue on the reverse postorder of the source label, so a
+    /// label is processed after all of its forward predecessors
+    ReversePostorder,
+    /// Chaotic iteration sweeping over every edge in reverse postorder until a
+    /// whole sweep changes nothing
+    RoundRobin,
+}
+
+impl Strategy {
+    pub fn from_name(name: &str) -> Option<Strategy> {
+        return match name {
+            "fifo" => Some(Strategy::Fifo),
+            "lifo" => Some(Strategy::Lifo),
+            "rpo" => Some(Strategy::ReversePostorder),
+            "round-robin" => Some(Strategy::RoundRobin),
+            _ => None,
+        };
+    }
+}
+
+/// Pending edges of the solver. `insert` is called for every edge leaving a
+/// label whose value changed.
+pub trait Worklist {
+    fn insert(&mut self, edge: Edge);
+    fn extract(&mut self) -> Option<Edge>;
+
+    /// Pending edges in the order they would be extracted
+    fn contents(&self) -> Vec<Edge>;
+}
+
+pub fn new_worklist(strategy: Strategy, cfg: &Cfg) -> Box<dyn Worklist> {
+    return match strategy {
+        Strategy::Fifo => Box::new(Fifo {
+            queue: VecDeque::new(),
+        }),
+        Strategy::Lifo => Box::new(Lifo { stack: Vec::new() }),
+        Strategy::ReversePostorder => Box::new(PriorityWorklist {
+            order: order(cfg),
+            queue: BinaryHeap::new(),
+            pending: HashSet::new(),
+        }),
+        Strategy::RoundRobin => {
+            let order = order(cfg);
+            let mut edges = cfg.edges().to_vec();
+            edges.sort_by_key(|(l1, l2)| (order[l1], order[l2]));
+            Box::new(RoundRobin {
+                next: edges.len(),
+                edges,
+                changed: false,
+            })
+        }
+    };
+}
+
+// Position of every label in the reverse postorder of the graph
+fn order(cfg: &Cfg) -> HashMap<Label, usize> {
+    return cfg
+        .reverse_postorder()
+        .into_iter()
+        .enumerate()
+        .map(|(i, l)| (l, i))
+        .collect();
+}
+
+struct Fifo {
+    queue: VecDeque<Edge>,
+}
+
+impl Worklist for Fifo {
+    fn insert(&mut self, edge: Edge) {
+        self.queue.push_back(edge);
+    }
+
+    fn extract(&mut self) -> Option<Edge> {
+        return self.queue.pop_front();
+    }
+
+    fn contents(&self) -> Vec<Edge> {
+        return self.queue.iter().copied().collect();
+    }
+}
+
+struct Lifo {
+    stack: Vec<Edge>,
+}
+
+impl Worklist for Lifo {
+    fn insert(&mut self, edge: Edge) {
+        self.stack.push(edge);
+    }
+
+    fn extract(&mut self) -> Option<Edge> {
+        return self.stack.pop();
+    }
+
+    fn contents(&self) -> Vec<Edge> {
+        return self.stack.iter().rev().copied().collect();
+    }
+}
+
+// Edges already waiting in the queue are not added a second time
+struct PriorityWorklist {
+    order: HashMap<Label, usize>,
+    queue: BinaryHeap<Reverse<(usize, usize, Edge)>>,
+    pending: HashSet<Edge>,
+}
+
+impl Worklist for PriorityWorklist {
+    fn insert(&mut self, edge: Edge) {
+        if self.pending.insert(edge) {
+            let (l1, l2) = edge;
+            self.queue
+                .push(Reverse((self.order[&l1], self.order[&l2], edge)));
+        }
+    }
+
+    fn extract(&mut self) -> Option<Edge> {
+        let Reverse((_, _, edge)) = self.queue.pop()?;
+        self.pending.remove(&edge);
+        return Some(edge);
+    }
+
+    fn contents(&self) -> Vec<Edge> {
+        let mut queue: Vec<_> = self.queue.iter().map(|Reverse(entry)| *entry).collect();
+        queue.sort();
+        return queue.into_iter().map(|(_, _, edge)| edge).collect();
+    }
+}
+
+// Inserted edges only mark the current sweep as changed, every sweep visits
+// all edges anyway. The first sweep starts once the initial edges are in.
+struct RoundRobin {
+    edges: Vec<Edge>,
+    next: usize,
+    changed: bool,
+}
+
+impl Worklist for RoundRobin {
+    fn insert(&mut self, _edge: Edge) {
+        self.changed = true;
+    }
+
+    fn extract(&mut self) -> Option<Edge> {
+        if self.next == self.edges.len() {
+            if !self.changed || self.edges.is_empty() {
+                return None;
+            }
+            self.next = 0;
+            self.changed = false;
+        }
+
+        self.next += 1;
+        return Some(self.edges[self.next - 1]);
+    }
+
+    fn contents(&self) -> Vec<Edge> {
+        if self.next == self.edges.len() && self.changed {
+            return self.edges.clone();
+        }
+        return self.edges[self.next..].to_vec();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    // Reverse postorder 1, 5, 2, 4, 3 with the back edges 3 -> 2 and 4 -> 1
+    fn nested_loop() -> Cfg {
+        return Cfg::new(
+            parse("while x > 0 do (while y > 0 do y := y - 1; x := x - 1); z := 1").unwrap(),
+        );
+    }
+
+    fn drain(worklist: &mut dyn Worklist) -> Vec<Edge> {
+        let mut edges = Vec::new();
+        while let Some(edge) = worklist.extract() {
+            edges.push(edge);
+        }
+        return edges;
+    }
+
+    fn filled(strategy: Strategy, edges: &[Edge]) -> Box<dyn Worklist> {
+        let mut worklist = new_worklist(strategy, &nested_loop());
+        for edge in edges {
+            worklist.insert(*edge);
+        }
+        return worklist;
+    }
+
+    #[test]
+    fn fifo_extracts_in_insertion_order() {
+        let mut worklist = filled(Strategy::Fifo, &[(2, 3), (1, 2), (4, 1)]);
+        assert_eq!(worklist.contents(), [(2, 3), (1, 2), (4, 1)]);
+        assert_eq!(drain(worklist.as_mut()), [(2, 3), (1, 2), (4, 1)]);
+    }
+
+    #[test]
+    fn lifo_extracts_the_latest_edge_first() {
+        let mut worklist = filled(Strategy::Lifo, &[(2, 3), (1, 2), (4, 1)]);
+        assert_eq!(worklist.contents(), [(4, 1), (1, 2), (2, 3)]);
+        assert_eq!(drain(worklist.as_mut()), [(4, 1), (1, 2), (2, 3)]);
+    }
+
+    #[test]
+    fn reverse_postorder_extracts_by_source_then_target_once() {
+        let mut worklist = filled(
+            Strategy::ReversePostorder,
+            &[(4, 1), (2, 3), (1, 5), (1, 2), (2, 4), (2, 3)],
+        );
+        let expected = [(1, 5), (1, 2), (2, 4), (2, 3), (4, 1)];
+        assert_eq!(worklist.contents(), expected);
+        assert_eq!(drain(worklist.as_mut()), expected);
+    }
+
+    #[test]
+    fn round_robin_sweeps_until_nothing_changes() {
+        let sweep = [(1, 5), (1, 2), (2, 4), (2, 3), (4, 1), (3, 2)];
+
+        assert_eq!(drain(filled(Strategy::RoundRobin, &[]).as_mut()), []);
+
+        let mut worklist = filled(Strategy::RoundRobin, &[(2, 3)]);
+        assert_eq!(worklist.contents(), sweep);
+        let mut extracted = Vec::new();
+        for _ in 0..sweep.len() {
+            extracted.push(worklist.extract().unwrap());
+        }
+        assert_eq!(extracted, sweep);
+
+        // A change in the finished sweep starts another one
+        worklist.insert((4, 1));
+        assert_eq!(drain(worklist.as_mut()), sweep);
+    }
+}
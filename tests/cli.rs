@@ -0,0 +1,44 @@
+#![allow(clippy::needless_return)]
+
+use std::process::{Command, Output};
+
+use serde_json::Value;
+
+/*
+   Runs the `dfa` binary on the examples in `programs/` and checks what it
+   writes to standard output and standard error.
+*/
+
+fn dfa(args: &[&str]) -> Output {
+    return Command::new(env!("CARGO_BIN_EXE_dfa"))
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .unwrap();
+}
+
+#[test]
+fn several_analyses_give_one_json_document() {
+    let output = dfa(&[
+        "analyze",
+        "-a",
+        "rd,lv",
+        "-f",
+        "json",
+        "programs/factorial.while",
+    ]);
+    assert!(output.status.success());
+
+    let json: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let object = json.as_object().unwrap();
+    let names: Vec<&String> = object.keys().collect();
+    assert_eq!(names, ["lv", "rd"]);
+    assert!(object["rd"].as_array().unwrap()[0]["entry"].is_array());
+}
+
+#[test]
+fn unknown_command_is_reported() {
+    let output = dfa(&["foo"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unknown command 'foo'"));
+}
@@ -0,0 +1,167 @@
+#![allow(clippy::needless_return)]
+
+use std::{env, fs, path::PathBuf};
+
+use while_lang_data_flow_analysis::{
+    abstract_syntax::Program,
+    ae::AvailableExpressions,
+    cp::ConstantPropagation,
+    domain::NARROWING_PASSES,
+    framework::solve,
+    interval::{IntervalAnalysis, IntervalLattice},
+    lv::LiveVariables,
+    parity::{ParityAnalysis, ParityLattice},
+    parser::parse,
+    rd::ReachingDefinition,
+    report::render_result,
+    sign::{SignAnalysis, SignLattice},
+    vb::VeryBusyExpressions,
+};
+
+/*
+   Regression tests against the results of the textbook examples in
+   `programs/`. Every bit vector analysis runs on the examples of PROGRAMS
+   and every value analysis on those of VALUE_PROGRAMS, and their ENTRY and
+   EXIT sets are compared with `tests/golden/<program>.<analysis>.txt`.
+
+   After an intended change of the results, regenerate the files with
+
+   UPDATE_GOLDEN=1 cargo test --test golden
+
+   and review the diff before committing it.
+*/
+
+const PROGRAMS: [&str; 4] = [
+    "available_expressions",
+    "factorial",
+    "live_variables",
+    "very_busy_expressions",
+];
+
+const VALUE_PROGRAMS: [&str; 5] = ["branches", "constants", "factorial", "loops", "signs"];
+
+fn root() -> PathBuf {
+    return PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+}
+
+fn load(name: &str) -> Box<Program> {
+    let path = root().join("programs").join(format!("{}.while", name));
+    let source = fs::read_to_string(&path).unwrap();
+    return match parse(&source) {
+        Ok(program) => program,
+        Err(error) => panic!("{}", error.to_diagnostic().render(&source)),
+    };
+}
+
+fn assert_golden(program: &str, analysis: &str, actual: String) {
+    let path = root()
+        .join("tests")
+        .join("golden")
+        .join(format!("{}.{}.txt", program, analysis));
+
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = match fs::read_to_string(&path) {
+        Ok(expected) => expected,
+        Err(_) => panic!(
+            "missing {}, run with UPDATE_GOLDEN=1 to create it",
+            path.display()
+        ),
+    };
+    assert_eq!(
+        actual,
+        expected,
+        "{} differs, run with UPDATE_GOLDEN=1 to accept the new result",
+        path.display()
+    );
+}
+
+#[test]
+fn available_expressions() {
+    for name in PROGRAMS {
+        let result = solve(Box::new(AvailableExpressions {
+            program: load(name),
+        }));
+        assert_golden(name, "ae", render_result(&result));
+    }
+}
+
+#[test]
+fn reaching_definitions() {
+    for name in PROGRAMS {
+        let result = solve(Box::new(ReachingDefinition {
+            program: load(name),
+        }));
+        assert_golden(name, "rd", render_result(&result));
+    }
+}
+
+#[test]
+fn live_variables() {
+    for name in PROGRAMS {
+        let result = solve(Box::new(LiveVariables {
+            program: load(name),
+        }));
+        assert_golden(name, "lv", render_result(&result));
+    }
+}
+
+#[test]
+fn very_busy_expressions() {
+    for name in PROGRAMS {
+        let result = solve(Box::new(VeryBusyExpressions {
+            program: load(name),
+        }));
+        assert_golden(name, "vb", render_result(&result));
+    }
+}
+
+#[test]
+fn constant_propagation() {
+    for name in VALUE_PROGRAMS {
+        let result = solve(Box::new(ConstantPropagation {
+            program: load(name),
+        }));
+        assert_golden(name, "cp", render_result(&result));
+    }
+}
+
+#[test]
+fn intervals() {
+    for name in VALUE_PROGRAMS {
+        let result = solve(Box::new(IntervalAnalysis::new(
+            load(name),
+            IntervalLattice {},
+            NARROWING_PASSES,
+        )));
+        assert_golden(name, "interval", render_result(&result));
+    }
+}
+
+#[test]
+fn signs() {
+    for name in VALUE_PROGRAMS {
+        let result = solve(Box::new(SignAnalysis::new(
+            load(name),
+            SignLattice {},
+            NARROWING_PASSES,
+        )));
+        assert_golden(name, "sign", render_result(&result));
+    }
+}
+
+#[test]
+fn parities() {
+    for name in VALUE_PROGRAMS {
+        let result = solve(Box::new(ParityAnalysis::new(
+            load(name),
+            ParityLattice {},
+            NARROWING_PASSES,
+        )));
+        assert_golden(name, "parity", render_result(&result));
+    }
+}
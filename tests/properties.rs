@@ -0,0 +1,235 @@
+#![allow(clippy::needless_return)]
+
+use std::collections::HashSet;
+
+use while_lang_data_flow_analysis::{
+    abstract_syntax::Program,
+    ae::AvailableExpressions,
+    cp::ConstantPropagation,
+    framework::{solve, solve_with, Framework},
+    generator::{generate, ArithmeticOperator, Config},
+    interval::{IntervalAnalysis, IntervalLattice},
+    labelling::validate_labels,
+    lattice::Lattice,
+    lv::LiveVariables,
+    parity::{ParityAnalysis, ParityLattice},
+    parser::parse,
+    rd::ReachingDefinition,
+    sign::{SignAnalysis, SignLattice},
+    soundness::check_soundness,
+    utils::{assignments, blocks, flow, flow_r, fv_st, init, label, labels, r#final, variables},
+    vb::VeryBusyExpressions,
+    verify::verify_fixpoint,
+    worklist::Strategy,
+};
+
+// Properties of the textbook functions and the solver over random programs
+
+const PROGRAMS: u64 = 200;
+
+fn programs() -> impl Iterator<Item = (u64, Box<Program>)> {
+    return (0..PROGRAMS).map(|seed| (seed, generate(Config::default(), seed)));
+}
+
+fn edge_set(edges: Vec<(i64, i64)>) -> HashSet<(i64, i64)> {
+    return edges.into_iter().collect();
+}
+
+#[test]
+fn labels_are_unique_and_consecutive() {
+    for (seed, program) in programs() {
+        assert!(validate_labels(program.clone()).is_ok(), "seed {}", seed);
+
+        let mut ls = labels(program.clone());
+        ls.sort();
+        let expected: Vec<i64> = (1..=ls.len() as i64).collect();
+        assert_eq!(ls, expected, "seed {}", seed);
+    }
+}
+
+#[test]
+fn flow_r_is_the_reverse_of_flow() {
+    for (seed, program) in programs() {
+        let forward = flow(program.clone());
+        let backward = flow_r(program.clone());
+        assert_eq!(forward.len(), backward.len(), "seed {}", seed);
+
+        let reversed = edge_set(backward.into_iter().map(|(l1, l2)| (l2, l1)).collect());
+        assert_eq!(edge_set(forward), reversed, "seed {}", seed);
+    }
+}
+
+#[test]
+fn flow_connects_labels_of_the_program() {
+    for (seed, program) in programs() {
+        let ls: HashSet<i64> = labels(program.clone()).into_iter().collect();
+
+        assert!(ls.contains(&init(program.clone())), "seed {}", seed);
+        for l in r#final(program.clone()) {
+            assert!(ls.contains(&l), "seed {}", seed);
+        }
+        for (l1, l2) in flow(program.clone()) {
+            assert!(ls.contains(&l1) && ls.contains(&l2), "seed {}", seed);
+        }
+
+        // Every block is reachable from init along flow
+        let edges = flow(program.clone());
+        let mut reached = HashSet::from([init(program.clone())]);
+        let mut pending = Vec::from([init(program.clone())]);
+        while let Some(l) = pending.pop() {
+            for (l1, l2) in &edges {
+                if *l1 == l && reached.insert(*l2) {
+                    pending.push(*l2);
+                }
+            }
+        }
+        assert_eq!(reached, ls, "seed {}", seed);
+    }
+}
+
+#[test]
+fn blocks_and_variables_agree() {
+    for (seed, program) in programs() {
+        let from_blocks: Vec<i64> = blocks(program.clone()).into_iter().map(label).collect();
+        assert_eq!(from_blocks, labels(program.clone()), "seed {}", seed);
+
+        let all = variables(program.clone());
+        assert!(fv_st(program.clone()).is_subset(&all), "seed {}", seed);
+        for assignment in assignments(program.clone()) {
+            assert!(all.contains(&assignment.name), "seed {}", seed);
+        }
+    }
+}
+
+#[test]
+fn unlabelled_source_parses_back_to_the_same_program() {
+    for (seed, program) in programs() {
+        let source = format!("{:#}", program);
+        let parsed = match parse(&source) {
+            Ok(parsed) => parsed,
+            Err(error) => panic!("seed {}: {}\n{}", seed, error, source),
+        };
+
+        // The labelled rendering shows every block with its label, so equal
+        // renderings mean equal trees up to the spans
+        assert_eq!(
+            format!("{}", parsed),
+            format!("{}", program),
+            "seed {}",
+            seed
+        );
+        assert_eq!(flow(parsed), flow(program.clone()), "seed {}", seed);
+    }
+}
+
+fn check<L: Lattice>(framework: impl Fn() -> Box<dyn Framework<L>>, seed: u64) {
+    let result = solve(framework());
+    if let Err(errors) = verify_fixpoint(framework().as_ref(), &result) {
+        panic!("seed {}: {:?}", seed, errors[0]);
+    }
+}
+
+#[test]
+fn solver_results_are_fixpoints() {
+    for (seed, program) in programs() {
+        let p = || program.clone();
+        check(|| Box::new(ReachingDefinition { program: p() }), seed);
+        check(|| Box::new(AvailableExpressions { program: p() }), seed);
+        check(|| Box::new(LiveVariables { program: p() }), seed);
+        check(|| Box::new(VeryBusyExpressions { program: p() }), seed);
+        check(|| Box::new(ConstantPropagation { program: p() }), seed);
+        check(
+            || Box::new(IntervalAnalysis::new(p(), IntervalLattice {}, 2)),
+            seed,
+        );
+        check(|| Box::new(SignAnalysis::new(p(), SignLattice {}, 0)), seed);
+        check(
+            || Box::new(ParityAnalysis::new(p(), ParityLattice {}, 0)),
+            seed,
+        );
+    }
+}
+
+#[test]
+fn verifier_rejects_lowered_results() {
+    for (seed, program) in programs() {
+        let framework = ReachingDefinition { program };
+        let mut result = solve(Box::new(ReachingDefinition {
+            program: framework.program.clone(),
+        }));
+
+        // Away from the extremal label ENTRY is exactly the union over the
+        // incoming edges, so dropping a definition breaks one of them
+        let Some((l, x)) = result
+            .entry
+            .iter()
+            .filter(|(l, _)| **l != init(framework.program.clone()))
+            .find_map(|(l, entry)| Some((*l, entry.iter().next()?.clone())))
+        else {
+            continue;
+        };
+        result.entry.get_mut(&l).unwrap().remove(&x);
+
+        assert!(
+            verify_fixpoint(&framework, &result).is_err(),
+            "seed {}",
+            seed
+        );
+    }
+}
+
+#[test]
+fn strategies_agree() {
+    let strategies = [
+        Strategy::Fifo,
+        Strategy::Lifo,
+        Strategy::ReversePostorder,
+        Strategy::RoundRobin,
+    ];
+    for (seed, program) in programs() {
+        let results: Vec<_> = strategies
+            .iter()
+            .map(|strategy| {
+                solve_with(
+                    Box::new(ReachingDefinition {
+                        program: program.clone(),
+                    }),
+                    *strategy,
+                )
+            })
+            .collect();
+        for result in &results[1..] {
+            assert_eq!(result.entry, results[0].entry, "seed {}", seed);
+            assert_eq!(result.exit, results[0].exit, "seed {}", seed);
+        }
+    }
+}
+
+#[test]
+fn results_hold_for_concrete_runs() {
+    // Without division no run faults, so every block can be checked
+    let config = Config {
+        arithmetic: Vec::from([
+            ArithmeticOperator::Add,
+            ArithmeticOperator::Sub,
+            ArithmeticOperator::Mul,
+        ]),
+        ..Config::default()
+    };
+    for seed in 0..PROGRAMS {
+        let program = generate(config.clone(), seed);
+        let store = variables(program.clone())
+            .into_iter()
+            .map(|x| (x, (seed % 5) as i64))
+            .collect();
+
+        let violations = check_soundness(program.clone(), store, 200);
+        assert!(
+            violations.is_empty(),
+            "seed {}: {}\n{}",
+            seed,
+            violations[0],
+            program
+        );
+    }
+}